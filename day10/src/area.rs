@@ -0,0 +1,101 @@
+use crate::{Gameboard, Tile};
+
+// The corners of the loop, in walking order. Straight pieces add nothing to the outline.
+pub fn vertices(board: &Gameboard, main_loop: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    main_loop
+        .iter()
+        .filter(|coord| {
+            !matches!(
+                board.get(**coord),
+                Some(Tile::NorthSouth) | Some(Tile::EastWest)
+            )
+        })
+        .copied()
+        .collect()
+}
+
+fn twice_shoelace_area(vertices: &[(i32, i32)]) -> i64 {
+    let mut total = 0i64;
+    for (i, a) in vertices.iter().enumerate() {
+        let b = vertices[(i + 1) % vertices.len()];
+        total += a.0 as i64 * b.1 as i64 - b.0 as i64 * a.1 as i64;
+    }
+    total.abs()
+}
+
+// Every loop tile is a lattice point on the boundary, so Pick's theorem (A = I + B/2 - 1)
+// gives the tiles strictly inside straight from the area.
+pub fn enclosed_by_pick(board: &Gameboard, main_loop: &[(i32, i32)]) -> u64 {
+    let area = twice_shoelace_area(&vertices(board, main_loop));
+    ((area - main_loop.len() as i64) / 2 + 1) as u64
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Region {
+    Loop,
+    Inside,
+    Outside,
+}
+
+// Walks each row, flipping between outside and inside at every loop tile with a northward
+// connection. Slower, but shares nothing with the shoelace version.
+pub fn classify(board: &Gameboard, main_loop: &[(i32, i32)]) -> Vec<Vec<Region>> {
+    let mut regions: Vec<Vec<Region>> = board
+        .board
+        .iter()
+        .map(|line| vec![Region::Outside; line.len()])
+        .collect();
+    for coord in main_loop {
+        regions[coord.0 as usize][coord.1 as usize] = Region::Loop;
+    }
+
+    for (i, line) in board.board.iter().enumerate() {
+        let mut inside = false;
+        for (j, tile) in line.iter().enumerate() {
+            if regions[i][j] == Region::Loop {
+                if matches!(tile, Tile::NorthSouth | Tile::NorthEast | Tile::NorthWest) {
+                    inside = !inside;
+                }
+            } else if inside {
+                regions[i][j] = Region::Inside;
+            }
+        }
+    }
+    regions
+}
+
+pub fn enclosed_by_scanline(board: &Gameboard, main_loop: &[(i32, i32)]) -> u64 {
+    classify(board, main_loop)
+        .iter()
+        .flatten()
+        .filter(|region| **region == Region::Inside)
+        .count() as u64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn both(input: &str) -> (u64, u64) {
+        let board = Gameboard::from_str(input).unwrap();
+        let main_loop = board.main_loop();
+        (
+            enclosed_by_pick(&board, &main_loop),
+            enclosed_by_scanline(&board, &main_loop),
+        )
+    }
+
+    #[test]
+    fn test_methods_agree() {
+        assert_eq!(both(include_str!("../test_files/part_one.txt")), (1, 1));
+        assert_eq!(both(include_str!("../test_files/part_two.txt")), (10, 10));
+        assert_eq!(
+            both("...........\n.S-------7.\n.|F-----7|.\n.||.....||.\n.||.....||.\n.|L-7.F-J|.\n.|..|.|..|.\n.L--J.L--J.\n..........."),
+            (4, 4)
+        );
+        assert_eq!(
+            both(".F----7F7F7F7F-7....\n.|F--7||||||||FJ....\n.||.FJ||||||||L7....\nFJL7L7LJLJ||LJ.L-7..\nL--J.L7...LJS7F-7L7.\n....F-J..F7FJ|L7L7L7\n....L7.F7||L7|.L7L7|\n.....|FJLJ|FJ|F7|.LJ\n....FJL-7.||.||||...\n....L---J.LJ.LJLJ..."),
+            (8, 8)
+        );
+    }
+}
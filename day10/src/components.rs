@@ -0,0 +1,179 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::{add_pos, area, Direction, Gameboard};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Shape {
+    // The enclosed tile count comes along with the loop, as it is only defined for one.
+    Loop { enclosed: u64 },
+    Chain,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Component {
+    pub tiles: Vec<(i32, i32)>,
+    pub shape: Shape,
+    // Connectors that don't meet a pipe connecting back, by tile and the way they point.
+    pub dangling: Vec<((i32, i32), Direction)>,
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.shape {
+            Shape::Loop { enclosed } => write!(
+                f,
+                "Loop at {:?}: {} tiles, {} enclosed",
+                self.tiles[0],
+                self.tiles.len(),
+                enclosed
+            ),
+            Shape::Chain => {
+                write!(
+                    f,
+                    "Chain at {:?}: {} tiles, dangling",
+                    self.tiles[0],
+                    self.tiles.len()
+                )?;
+                for (coord, direction) in &self.dangling {
+                    write!(f, " {:?} {:?}", coord, direction)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn connects(board: &Gameboard, coord: (i32, i32), direction: Direction) -> bool {
+    board
+        .get(coord)
+        .and_then(|tile| tile.connections())
+        .is_some_and(|connections| connections.contains(&direction))
+}
+
+// Whether the pipe at `coord` is met by a pipe connecting back in `direction`.
+fn matched(board: &Gameboard, coord: (i32, i32), direction: Direction) -> bool {
+    let neighbour = add_pos(coord, direction.get_index_offsets());
+    connects(board, neighbour, direction.opposite())
+}
+
+// Every pipe has at most two connectors, so each component is either a loop or a chain.
+pub fn analyse(board: &Gameboard) -> Vec<Component> {
+    let mut seen: Vec<Vec<bool>> = board
+        .board
+        .iter()
+        .map(|line| vec![false; line.len()])
+        .collect();
+    let mut components = vec![];
+
+    for (i, line) in board.board.iter().enumerate() {
+        for (j, tile) in line.iter().enumerate() {
+            if seen[i][j] || tile.connections().is_none() {
+                continue;
+            }
+
+            let mut tiles = vec![];
+            let mut dangling = vec![];
+            let mut queue = VecDeque::from([(i as i32, j as i32)]);
+            seen[i][j] = true;
+            while let Some(coord) = queue.pop_front() {
+                tiles.push(coord);
+                for direction in board.get(coord).unwrap().connections().unwrap() {
+                    if !matched(board, coord, direction) {
+                        dangling.push((coord, direction));
+                        continue;
+                    }
+                    let neighbour = add_pos(coord, direction.get_index_offsets());
+                    let seen = &mut seen[neighbour.0 as usize][neighbour.1 as usize];
+                    if !*seen {
+                        *seen = true;
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+
+            let shape = if dangling.is_empty() {
+                let path = board.walk_loop(tiles[0]);
+                Shape::Loop {
+                    enclosed: area::enclosed_by_pick(board, &path),
+                }
+            } else {
+                Shape::Chain
+            };
+            tiles.sort();
+            components.push(Component {
+                tiles,
+                shape,
+                dangling,
+            });
+        }
+    }
+    components
+}
+
+// Tiles with at least one connector that leads nowhere.
+pub fn mismatched_tiles(components: &[Component]) -> Vec<(i32, i32)> {
+    let mut tiles: Vec<_> = components
+        .iter()
+        .flat_map(|component| component.dangling.iter().map(|(coord, _)| *coord))
+        .collect();
+    tiles.sort();
+    tiles.dedup();
+    tiles
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_main_loop_only() {
+        let board = Gameboard::from_str(".....\n.S-7.\n.|.|.\n.L-J.\n.....").unwrap();
+        let components = analyse(&board);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].tiles.len(), 8);
+        assert_eq!(components[0].shape, Shape::Loop { enclosed: 1 });
+        assert!(mismatched_tiles(&components).is_empty());
+    }
+
+    #[test]
+    fn test_every_component() {
+        let board = Gameboard::from_str("-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF").unwrap();
+        let components = analyse(&board);
+
+        let main_loop = &components[board_component(&components, board.start_coords)];
+        assert_eq!(main_loop.shape, Shape::Loop { enclosed: 1 });
+        assert_eq!(main_loop.tiles.len(), 8);
+
+        let chains = components
+            .iter()
+            .filter(|c| c.shape == Shape::Chain)
+            .count();
+        assert_eq!(chains, components.len() - 1);
+        // Everything on the edge points off the board or at a pipe that doesn't connect back.
+        assert!(mismatched_tiles(&components).contains(&(0, 0)));
+        assert!(!mismatched_tiles(&components).contains(&board.start_coords));
+    }
+
+    #[test]
+    fn test_second_loop_and_chain() {
+        let board = Gameboard::from_str("S7.F-7\nLJ.|.|\n...L-J\n-7....").unwrap();
+        let components = analyse(&board);
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0].shape, Shape::Loop { enclosed: 0 });
+        assert_eq!(components[1].shape, Shape::Loop { enclosed: 1 });
+        assert_eq!(components[2].shape, Shape::Chain);
+        assert_eq!(
+            components[2].dangling,
+            vec![((3, 0), Direction::West), ((3, 1), Direction::South)]
+        );
+        assert_eq!(mismatched_tiles(&components), vec![(3, 0), (3, 1)]);
+    }
+
+    fn board_component(components: &[Component], coord: (i32, i32)) -> usize {
+        components
+            .iter()
+            .position(|c| c.tiles.contains(&coord))
+            .unwrap()
+    }
+}
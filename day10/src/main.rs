@@ -1,3 +1,9 @@
+use std::fmt;
+
+mod area;
+mod components;
+mod render;
+
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 enum Direction {
     North,
@@ -60,6 +66,35 @@ impl Tile {
         }
     }
 
+    fn connections(&self) -> Option<[Direction; 2]> {
+        match self {
+            Tile::NorthEast => Some([Direction::North, Direction::East]),
+            Tile::NorthSouth => Some([Direction::North, Direction::South]),
+            Tile::NorthWest => Some([Direction::North, Direction::West]),
+            Tile::EastSouth => Some([Direction::East, Direction::South]),
+            Tile::EastWest => Some([Direction::East, Direction::West]),
+            Tile::SouthWest => Some([Direction::South, Direction::West]),
+            Tile::Empty | Tile::Start => None,
+        }
+    }
+
+    fn from_connections(a: Direction, b: Direction) -> Self {
+        [
+            Tile::NorthEast,
+            Tile::NorthSouth,
+            Tile::NorthWest,
+            Tile::EastSouth,
+            Tile::EastWest,
+            Tile::SouthWest,
+        ]
+        .into_iter()
+        .find(|tile| {
+            let connections = tile.connections().unwrap();
+            connections.contains(&a) && connections.contains(&b)
+        })
+        .unwrap()
+    }
+
     fn get_allowed_incoming(dir: &Direction) -> [Self; 3] {
         match dir {
             Direction::North => Self::SOUTH_TILES,
@@ -118,19 +153,38 @@ impl Tile {
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+enum BoardError {
+    MissingStart,
+    StartConnections(usize),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::MissingStart => write!(f, "The board has no start tile"),
+            BoardError::StartConnections(count) => write!(
+                f,
+                "The start tile needs exactly two connecting pipes, but has {}",
+                count
+            ),
+        }
+    }
+}
+
 struct Gameboard {
     start_coords: (i32, i32),
     board: Vec<Vec<Tile>>,
 }
 
 impl Gameboard {
-    fn from_str(input: &str) -> Self {
+    fn from_str(input: &str) -> Result<Self, BoardError> {
         let board: Vec<Vec<Tile>> = input
             .lines()
             .map(|line| line.chars().map(Tile::from_char).collect())
             .collect();
 
-        Gameboard {
+        let mut gameboard = Gameboard {
             start_coords: board
                 .iter()
                 .enumerate()
@@ -144,9 +198,52 @@ impl Gameboard {
                     });
                     j.map(|j| (i as i32, j as i32))
                 })
-                .unwrap(),
+                .ok_or(BoardError::MissingStart)?,
             board,
+        };
+
+        // The start is whichever pipe joins the neighbours that connect back to it.
+        let connections: Vec<Direction> = Direction::CARDINALS
+            .into_iter()
+            .filter(|direction| {
+                let checking_pos = add_pos(gameboard.start_coords, direction.get_index_offsets());
+                gameboard
+                    .get(checking_pos)
+                    .is_some_and(|tile| Tile::get_allowed_incoming(direction).contains(&tile))
+            })
+            .collect();
+        if connections.len() != 2 {
+            return Err(BoardError::StartConnections(connections.len()));
         }
+        gameboard.set(
+            gameboard.start_coords,
+            Tile::from_connections(connections[0], connections[1]),
+        );
+
+        Ok(gameboard)
+    }
+
+    // The coordinates of the loop through the start, in walking order.
+    fn main_loop(&self) -> Vec<(i32, i32)> {
+        self.walk_loop(self.start_coords)
+    }
+
+    // Follows the pipes from `start` until they lead back to it, so they have to form a loop.
+    fn walk_loop(&self, start: (i32, i32)) -> Vec<(i32, i32)> {
+        let start_tile = self.get(start).unwrap();
+        let mut direction = start_tile.connections().unwrap()[0];
+        let mut current_coord = start;
+        let mut path = vec![];
+        loop {
+            path.push(current_coord);
+            current_coord = add_pos(current_coord, direction.get_index_offsets());
+            if current_coord == start {
+                break;
+            }
+            let current_tile = self.get(current_coord).unwrap();
+            direction = current_tile.get_remaining_direction(&direction.opposite());
+        }
+        path
     }
 
     fn get(&self, pos: (i32, i32)) -> Option<Tile> {
@@ -171,121 +268,56 @@ fn add_pos(a: (i32, i32), b: (i32, i32)) -> (i32, i32) {
 }
 
 fn part_one(input: &str) -> u32 {
-    let board = Gameboard::from_str(input);
-
-    let mut current_coord = (-1, -1);
-    let mut prev_direction = Direction::North;
-    for direction in Direction::CARDINALS {
-        let checking_pos = add_pos(board.start_coords, direction.get_index_offsets());
-        if let Some(tile) = board.get(checking_pos) {
-            if Tile::get_allowed_incoming(&direction).contains(&tile) {
-                current_coord = checking_pos;
-                prev_direction = direction.opposite();
-                break;
-            }
-        }
-    }
-
-    let mut path_length = 1;
-    loop {
-        if board.get(current_coord).unwrap() == Tile::Start {
-            break;
-        }
-
-        let current_tile = board.get(current_coord).unwrap();
-        let new_direction = current_tile.get_remaining_direction(&prev_direction);
-        current_coord = add_pos(current_coord, new_direction.get_index_offsets());
-        prev_direction = new_direction.opposite();
-
-        path_length += 1;
-    }
-
-    path_length / 2
-}
-
-// Based on https://wrfranklin.org/Research/Short_Notes/pnpoly.html
-fn is_in_polygon(polygon_x: &[i32], polygon_y: &[i32], point_x: i32, point_y: i32) -> bool {
-    let mut j = polygon_x.len() - 1;
-    let mut c = false;
-
-    for i in 0..polygon_x.len() {
-        if ((polygon_y[i] > point_y) != (polygon_y[j] > point_y))
-            && (point_x
-                < (polygon_x[j] - polygon_x[i]) * (point_y - polygon_y[i])
-                    / (polygon_y[j] - polygon_y[i])
-                    + polygon_x[i])
-        {
-            c = !c;
-        }
-        j = i;
-    }
-
-    c
+    let board = Gameboard::from_str(input).unwrap_or_else(|e| panic!("{}", e));
+    board.main_loop().len() as u32 / 2
 }
 
 fn part_two(input: &str) -> u32 {
-    let mut board = Gameboard::from_str(input);
+    let board = Gameboard::from_str(input).unwrap_or_else(|e| panic!("{}", e));
+    let main_loop = board.main_loop();
+    let enclosed = area::enclosed_by_pick(&board, &main_loop);
+    debug_assert_eq!(enclosed, area::enclosed_by_scanline(&board, &main_loop));
+    enclosed as u32
+}
 
-    let mut current_coord = (-1, -1);
-    let mut prev_direction = Direction::North;
+fn main() {
+    let input = include_str!("../input.txt");
 
-    for direction in Direction::CARDINALS {
-        let checking_pos = add_pos(board.start_coords, direction.get_index_offsets());
-        if let Some(tile) = board.get(checking_pos) {
-            if Tile::get_allowed_incoming(&direction).contains(&tile) {
-                current_coord = checking_pos;
-                prev_direction = direction.opposite();
-                break;
-            }
+    // `render [--colour]` draws the maze, `ppm <path>` writes it out as an image and
+    // `components` lists every group of connected pipes.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("render") => {
+            let board = Gameboard::from_str(input).unwrap_or_else(|e| panic!("{}", e));
+            let style = if args.iter().any(|a| a == "--colour") {
+                render::Style::Ansi
+            } else {
+                render::Style::Plain
+            };
+            print!("{}", render::render(&board, style));
+            return;
         }
-    }
-
-    let mut polygon_x = vec![];
-    let mut polygon_y = vec![];
-
-    loop {
-        if board.get(current_coord).unwrap() == Tile::Start {
-            break;
+        Some("ppm") => {
+            let board = Gameboard::from_str(input).unwrap_or_else(|e| panic!("{}", e));
+            let path = args.get(1).expect("Expected a path to write the image to");
+            std::fs::write(path, render::to_ppm(&board)).unwrap();
+            return;
         }
-
-        let current_tile = board.get(current_coord).unwrap();
-        match current_tile {
-            Tile::NorthEast | Tile::SouthWest | Tile::NorthWest | Tile::EastSouth => {
-                polygon_x.push(current_coord.0);
-                polygon_y.push(current_coord.1);
+        Some("components") => {
+            let board = Gameboard::from_str(input).unwrap_or_else(|e| panic!("{}", e));
+            let components = components::analyse(&board);
+            for component in &components {
+                println!("{}", component);
             }
-            Tile::EastWest | Tile::NorthSouth => {}
-            Tile::Start | Tile::Empty => unreachable!(),
+            println!(
+                "Mismatched tiles: {:?}",
+                components::mismatched_tiles(&components)
+            );
+            return;
         }
-        board.set(current_coord, Tile::Start);
-
-        let new_direction = current_tile.get_remaining_direction(&prev_direction);
-        current_coord = add_pos(current_coord, new_direction.get_index_offsets());
-        prev_direction = new_direction.opposite();
+        _ => {}
     }
 
-    board
-        .board
-        .iter()
-        .enumerate()
-        .map(|(i, line)| {
-            line.iter()
-                .enumerate()
-                .filter(|(j, s)| {
-                    if s != &&Tile::Start {
-                        is_in_polygon(&polygon_x, &polygon_y, i as i32, *j as i32)
-                    } else {
-                        false
-                    }
-                })
-                .count()
-        })
-        .sum::<usize>() as u32
-}
-
-fn main() {
-    let input = include_str!("../input.txt");
-
     println!("Part one: {}", part_one(input));
     println!("Part two: {}", part_two(input));
 }
@@ -305,4 +337,35 @@ mod test {
         let input = include_str!("../test_files/part_two.txt");
         assert_eq!(part_two(input), 10)
     }
+
+    #[test]
+    fn test_start_shape() {
+        let board = Gameboard::from_str(include_str!("../test_files/part_one.txt")).unwrap();
+        assert_eq!(board.get(board.start_coords), Some(Tile::EastSouth));
+
+        // With the start in a corner of the loop, it has to count as a vertex too.
+        let board = Gameboard::from_str(".....\n.S-7.\n.|.|.\n.L-J.\n.....").unwrap();
+        assert_eq!(board.get(board.start_coords), Some(Tile::EastSouth));
+        assert_eq!(part_two(".....\n.S-7.\n.|.|.\n.L-J.\n....."), 1);
+    }
+
+    #[test]
+    fn test_start_errors() {
+        assert!(matches!(
+            Gameboard::from_str("..\n.."),
+            Err(BoardError::MissingStart)
+        ));
+        assert!(matches!(
+            Gameboard::from_str("...\n.S.\n..."),
+            Err(BoardError::StartConnections(0))
+        ));
+        assert!(matches!(
+            Gameboard::from_str("...\n.S-\n..."),
+            Err(BoardError::StartConnections(1))
+        ));
+        assert!(matches!(
+            Gameboard::from_str(".|.\n-S-\n.|."),
+            Err(BoardError::StartConnections(4))
+        ));
+    }
 }
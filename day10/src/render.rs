@@ -0,0 +1,148 @@
+use crate::area::{self, Region};
+use crate::{Direction, Gameboard, Tile};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Style {
+    // Loop pipes in double lines, with distinct glyphs for enclosed and outside tiles.
+    Plain,
+    // Every tile keeps its own glyph, with the region shown by colour instead.
+    Ansi,
+}
+
+fn light_glyph(tile: Tile) -> char {
+    match tile {
+        Tile::NorthEast => '└',
+        Tile::NorthSouth => '│',
+        Tile::NorthWest => '┘',
+        Tile::EastSouth => '┌',
+        Tile::EastWest => '─',
+        Tile::SouthWest => '┐',
+        Tile::Empty => '·',
+        Tile::Start => 'S',
+    }
+}
+
+fn double_glyph(tile: Tile) -> char {
+    match tile {
+        Tile::NorthEast => '╚',
+        Tile::NorthSouth => '║',
+        Tile::NorthWest => '╝',
+        Tile::EastSouth => '╔',
+        Tile::EastWest => '═',
+        Tile::SouthWest => '╗',
+        Tile::Empty => '·',
+        Tile::Start => 'S',
+    }
+}
+
+const INSIDE_GLYPH: char = '█';
+const OUTSIDE_GLYPH: char = ' ';
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+fn ansi_colour(region: Region, is_start: bool) -> &'static str {
+    match (region, is_start) {
+        (_, true) => "\x1b[1;31m",
+        (Region::Loop, false) => "\x1b[1;33m",
+        (Region::Inside, false) => "\x1b[32m",
+        (Region::Outside, false) => "\x1b[2;34m",
+    }
+}
+
+pub fn render(board: &Gameboard, style: Style) -> String {
+    let regions = area::classify(board, &board.main_loop());
+    let mut out = String::new();
+    for (i, line) in board.board.iter().enumerate() {
+        for (j, tile) in line.iter().enumerate() {
+            let region = regions[i][j];
+            match style {
+                Style::Plain => out.push(match region {
+                    Region::Loop => double_glyph(*tile),
+                    Region::Inside => INSIDE_GLYPH,
+                    Region::Outside => OUTSIDE_GLYPH,
+                }),
+                Style::Ansi => {
+                    let is_start = (i as i32, j as i32) == board.start_coords;
+                    out.push_str(ansi_colour(region, is_start));
+                    out.push(light_glyph(*tile));
+                }
+            }
+        }
+        if style == Style::Ansi {
+            out.push_str(ANSI_RESET);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// Each tile becomes a 3x3 block of pixels with its pipe drawn through the middle.
+const TILE_PIXELS: usize = 3;
+
+fn pixel_colour(region: Region, is_pipe: bool) -> [u8; 3] {
+    match (region, is_pipe) {
+        (Region::Loop, true) => [255, 200, 0],
+        (Region::Loop, false) => [40, 40, 40],
+        (Region::Inside, true) => [0, 120, 0],
+        (Region::Inside, false) => [0, 200, 0],
+        (Region::Outside, true) => [90, 90, 140],
+        (Region::Outside, false) => [20, 20, 60],
+    }
+}
+
+pub fn to_ppm(board: &Gameboard) -> Vec<u8> {
+    let regions = area::classify(board, &board.main_loop());
+    let height = board.board.len() * TILE_PIXELS;
+    let width = board.board[0].len() * TILE_PIXELS;
+
+    let mut out = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for y in 0..height {
+        for x in 0..width {
+            let (i, j) = (y / TILE_PIXELS, x / TILE_PIXELS);
+            let tile = board.board[i][j];
+            let offset = ((y % TILE_PIXELS) as i32 - 1, (x % TILE_PIXELS) as i32 - 1);
+            let is_pipe = match tile.connections() {
+                Some(connections) => {
+                    offset == (0, 0)
+                        || Direction::CARDINALS.iter().any(|direction| {
+                            connections.contains(direction)
+                                && direction.get_index_offsets() == offset
+                        })
+                }
+                None => false,
+            };
+            out.extend(pixel_colour(regions[i][j], is_pipe));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_plain() {
+        let board = Gameboard::from_str(".....\n.S-7.\n.|.|.\n.L-J.\n.....").unwrap();
+        assert_eq!(
+            render(&board, Style::Plain),
+            "     \n ╔═╗ \n ║█║ \n ╚═╝ \n     \n"
+        );
+    }
+
+    #[test]
+    fn test_ansi() {
+        let board = Gameboard::from_str(".S7\n.LJ").unwrap();
+        let rendered = render(&board, Style::Ansi);
+        assert!(rendered.starts_with("\x1b[2;34m·\x1b[1;31m┌\x1b[1;33m┐\x1b[0m\n"));
+    }
+
+    #[test]
+    fn test_ppm() {
+        let board = Gameboard::from_str(include_str!("../test_files/part_one.txt")).unwrap();
+        let ppm = to_ppm(&board);
+        let header = b"P6\n15 15\n255\n";
+        assert!(ppm.starts_with(header));
+        assert_eq!(ppm.len(), header.len() + 15 * 15 * 3);
+    }
+}
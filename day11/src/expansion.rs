@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+// How far the universe expands. A factor of N replaces each empty line with N lines, so 1
+// leaves it untouched and 0 removes it. Lines with a galaxy on them never expand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expansion {
+    pub rows: u128,
+    pub columns: u128,
+    // Factors for single empty lines, overriding the ones for their axis.
+    pub row_factors: HashMap<usize, u128>,
+    pub column_factors: HashMap<usize, u128>,
+}
+
+impl Expansion {
+    pub fn new(rows: u128, columns: u128) -> Self {
+        Expansion {
+            rows,
+            columns,
+            row_factors: HashMap::new(),
+            column_factors: HashMap::new(),
+        }
+    }
+
+    pub fn uniform(factor: u128) -> Self {
+        Self::new(factor, factor)
+    }
+
+    pub fn with_row_factor(mut self, row: usize, factor: u128) -> Self {
+        self.row_factors.insert(row, factor);
+        self
+    }
+
+    pub fn with_column_factor(mut self, column: usize, factor: u128) -> Self {
+        self.column_factors.insert(column, factor);
+        self
+    }
+
+    // The galaxies in reading order, as (row, column) in the expanded universe.
+    pub fn galaxies(&self, input: &str) -> Vec<(u128, u128)> {
+        let (rows, columns): (Vec<_>, Vec<_>) = galaxy_coords(input).into_iter().unzip();
+        let x_size = input.lines().map(str::len).max().unwrap_or(0);
+        let y_size = input.lines().count();
+
+        let rows = expanded_positions(&rows, y_size, |row| {
+            *self.row_factors.get(&row).unwrap_or(&self.rows)
+        });
+        let columns = expanded_positions(&columns, x_size, |column| {
+            *self.column_factors.get(&column).unwrap_or(&self.columns)
+        });
+        rows.into_iter().zip(columns).collect()
+    }
+}
+
+fn galaxy_coords(input: &str) -> Vec<(usize, usize)> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            line.char_indices()
+                .filter_map(move |(j, c)| if c == '#' { Some((i, j)) } else { None })
+        })
+        .collect()
+}
+
+// Maps every line along one axis to its expanded position, which is how many lines all the
+// ones before it have turned into.
+fn expanded_positions(
+    positions: &[usize],
+    size: usize,
+    factor: impl Fn(usize) -> u128,
+) -> Vec<u128> {
+    let mut occupied = vec![false; size];
+    positions.iter().for_each(|p| occupied[*p] = true);
+
+    let mut next = 0;
+    let starts: Vec<u128> = occupied
+        .iter()
+        .enumerate()
+        .map(|(line, occupied)| {
+            let start = next;
+            next += if *occupied { 1 } else { factor(line) };
+            start
+        })
+        .collect();
+    positions.iter().map(|p| starts[*p]).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_per_line_factors() {
+        let input = "#..\n...\n..#";
+        assert_eq!(Expansion::uniform(1).galaxies(input), vec![(0, 0), (2, 2)]);
+        assert_eq!(Expansion::new(3, 0).galaxies(input), vec![(0, 0), (4, 1)]);
+        assert_eq!(
+            Expansion::uniform(2)
+                .with_row_factor(1, 10)
+                .with_column_factor(1, 1)
+                .galaxies(input),
+            vec![(0, 0), (11, 2)]
+        );
+    }
+
+    #[test]
+    fn test_factors_on_galaxy_lines_are_ignored() {
+        let input = "#..\n...\n..#";
+        let expansion = Expansion::uniform(1)
+            .with_row_factor(0, 5)
+            .with_column_factor(2, 5);
+        assert_eq!(expansion.galaxies(input), vec![(0, 0), (2, 2)]);
+    }
+}
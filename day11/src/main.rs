@@ -1,62 +1,157 @@
-use itertools::Itertools;
+use std::fmt::Write;
 
-fn get_distances(input: &str, expansion_factor: usize) -> u128 {
-    let mut galaxy_coords = input
-        .lines()
-        .enumerate()
-        .flat_map(|(i, line)| {
-            line.char_indices()
-                .filter_map(move |(j, c)| if c == '#' { Some((i, j)) } else { None })
-        })
-        .collect::<Vec<_>>();
-
-    let x_size = input.lines().next().unwrap().len();
-    let y_size = input.lines().count();
-
-    let mut offset = 0;
-    for x in 0..x_size {
-        if !galaxy_coords.iter().any(|(_, j)| (x + offset) == *j) {
-            galaxy_coords
-                .iter_mut()
-                .filter(|(_, j)| *j > (x + offset))
-                .for_each(|g| g.1 += expansion_factor);
-            offset += expansion_factor;
-        }
-    }
+use expansion::Expansion;
+use universe::{Metric, Universe};
 
-    let mut offset = 0;
-    for y in 0..y_size {
-        if !galaxy_coords.iter().any(|(i, _)| (y + offset) == *i) {
-            galaxy_coords
-                .iter_mut()
-                .filter(|(i, _)| *i > (y + offset))
-                .for_each(|g| g.0 += expansion_factor);
-            offset += expansion_factor;
-        }
+mod expansion;
+mod universe;
+
+// Once sorted, each value is the far end of a pair with every value before it, so it adds
+// itself that many times minus everything it was paired with.
+fn axis_distance_sum(mut values: Vec<u128>) -> u128 {
+    values.sort_unstable();
+    let mut before = 0;
+    let mut total = 0;
+    for (i, value) in values.iter().enumerate() {
+        total += value * i as u128 - before;
+        before += value;
     }
+    total
+}
 
-    galaxy_coords
-        .iter()
-        .combinations(2)
-        .map(|c| {
-            let a = c[0];
-            let b = c[1];
-            ((a.0 as i128 - b.0 as i128).abs() + (a.1 as i128 - b.1 as i128).abs()) as u128
-        })
-        .sum()
+// Manhattan distance splits by axis, so the pairs never have to be visited one by one.
+fn total_distance(input: &str, expansion: &Expansion) -> u128 {
+    let (rows, columns) = expansion.galaxies(input).into_iter().unzip();
+    axis_distance_sum(rows) + axis_distance_sum(columns)
+}
+
+// Kept for callers that count the lines added to each empty one, rather than the lines it
+// is replaced with.
+fn get_distances(input: &str, expansion_factor: usize) -> u128 {
+    total_distance(input, &Expansion::uniform(expansion_factor as u128 + 1))
 }
 
 fn part_one(input: &str) -> u32 {
-    get_distances(input, 1) as u32
+    total_distance(input, &Expansion::uniform(2)) as u32
 }
 
 fn part_two(input: &str) -> u128 {
-    get_distances(input, 1_000_000 - 1)
+    total_distance(input, &Expansion::uniform(1_000_000))
+}
+
+// Reads `rows=N`, `columns=N`, `row:I=N` and `column:I=N` settings, where each empty line is
+// replaced with N lines, or `added=N` on its own for N lines added to every empty one.
+fn custom_distance(input: &str, settings: &[String]) -> Result<u128, String> {
+    let mut expansion = Expansion::uniform(2);
+    for setting in settings {
+        let (key, value) = setting
+            .split_once('=')
+            .ok_or_else(|| format!("Expected `key=value`, got {}", setting))?;
+        let value: u128 = value
+            .parse()
+            .map_err(|_| format!("Invalid factor {}", value))?;
+        let line = |index: &str| {
+            index
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid line {}", index))
+        };
+        match key.split_once(':') {
+            None if key == "added" => {
+                if settings.len() > 1 {
+                    return Err("`added` can not be combined with other settings".to_string());
+                }
+                let added =
+                    usize::try_from(value).map_err(|_| format!("Invalid factor {}", value))?;
+                return Ok(get_distances(input, added));
+            }
+            None if key == "rows" => expansion.rows = value,
+            None if key == "columns" => expansion.columns = value,
+            Some(("row", index)) => expansion = expansion.with_row_factor(line(index)?, value),
+            Some(("column", index)) => {
+                expansion = expansion.with_column_factor(line(index)?, value)
+            }
+            _ => return Err(format!("Unknown setting {}", key)),
+        }
+    }
+    Ok(total_distance(input, &expansion))
+}
+
+fn galaxy_number(universe: &Universe, arg: Option<&String>) -> Result<usize, String> {
+    let arg = arg.ok_or("Expected a galaxy number")?;
+    match arg.parse() {
+        Ok(number) if universe.galaxy(number).is_some() => Ok(number),
+        _ => Err(format!("No galaxy {}", arg)),
+    }
+}
+
+// Answers `pair <a> <b>`, `neighbours` and `histogram <width>` about the universe from part
+// one, each optionally followed by a metric.
+fn query(input: &str, args: &[String]) -> Result<String, String> {
+    let universe = Universe::new(input, &Expansion::uniform(2));
+    let metric = |index: usize| {
+        args.get(index)
+            .map_or(Ok(Metric::Manhattan), |m| Metric::from_str(m))
+    };
+
+    let mut out = String::new();
+    match args[0].as_str() {
+        "pair" => {
+            let a = galaxy_number(&universe, args.get(1))?;
+            let b = galaxy_number(&universe, args.get(2))?;
+            writeln!(out, "{}", universe.distance(a, b, metric(3)?).unwrap()).unwrap();
+        }
+        "neighbours" => {
+            let metric = metric(1)?;
+            for number in 1..=universe.len() {
+                if let (Some(nearest), Some(farthest)) = (
+                    universe.nearest(number, metric),
+                    universe.farthest(number, metric),
+                ) {
+                    writeln!(
+                        out,
+                        "{}: nearest {} ({}), farthest {} ({})",
+                        number, nearest.0, nearest.1, farthest.0, farthest.1
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        "histogram" => {
+            let width: f64 = match args.get(1).map(|w| w.parse()) {
+                Some(Ok(width)) if width > 0.0 => width,
+                _ => return Err("Expected a positive bucket width".to_string()),
+            };
+            for (bucket, pairs) in universe.histogram(metric(2)?, width) {
+                writeln!(out, "{:>8}: {}", bucket as f64 * width, pairs).unwrap();
+            }
+        }
+        query => return Err(format!("Unknown query {}", query)),
+    }
+    Ok(out)
 }
 
 fn main() {
     let input = include_str!("../input.txt");
 
+    let settings: Vec<String> = std::env::args().skip(1).collect();
+    if matches!(
+        settings.first().map(String::as_str),
+        Some("pair" | "neighbours" | "histogram")
+    ) {
+        match query(input, &settings) {
+            Ok(out) => print!("{}", out),
+            Err(e) => eprintln!("{}", e),
+        }
+        return;
+    }
+    if !settings.is_empty() {
+        match custom_distance(input, &settings) {
+            Ok(total) => println!("Total distance: {}", total),
+            Err(e) => eprintln!("{}", e),
+        }
+        return;
+    }
+
     println!("Part one: {}", part_one(input));
     println!("Part two: {}", part_two(input));
 }
@@ -82,4 +177,53 @@ mod test {
         let input = include_str!("../test_files/part_one_and_two.txt");
         assert_eq!(get_distances(input, 100 - 1), 8410)
     }
+
+    #[test]
+    fn test_matches_every_pair() {
+        let input = "#..#....\n........\n.#....#.\n........\n....#..#\n#.......";
+        let expansion = Expansion::new(4, 7).with_row_factor(3, 0);
+        let galaxies = expansion.galaxies(input);
+
+        let mut expected = 0;
+        for (i, a) in galaxies.iter().enumerate() {
+            for b in &galaxies[i + 1..] {
+                expected += a.0.abs_diff(b.0) + a.1.abs_diff(b.1);
+            }
+        }
+        assert_eq!(total_distance(input, &expansion), expected);
+    }
+
+    #[test]
+    fn test_separate_axes() {
+        let input = include_str!("../test_files/part_one_and_two.txt");
+        // Two empty rows and three empty columns, crossed by 36 and 46 pairs of galaxies.
+        let base = total_distance(input, &Expansion::uniform(1));
+        assert_eq!(total_distance(input, &Expansion::new(2, 1)), base + 36);
+        assert_eq!(total_distance(input, &Expansion::new(1, 2)), base + 46);
+        assert_eq!(total_distance(input, &Expansion::uniform(10)), 1030);
+    }
+
+    #[test]
+    fn test_custom_distance() {
+        let input = include_str!("../test_files/part_one_and_two.txt");
+        let settings = |s: &str| -> Vec<String> { s.split(' ').map(str::to_string).collect() };
+        assert_eq!(custom_distance(input, &settings("added=9")), Ok(1030));
+        assert!(custom_distance(input, &settings("rows=1 added=9")).is_err());
+        assert!(custom_distance(input, &settings("added=9 rows=1")).is_err());
+        assert!(custom_distance(
+            input,
+            &settings("added=340282366920938463463374607431768211455")
+        )
+        .is_err());
+        assert_eq!(
+            custom_distance(input, &settings("rows=1 columns=2")),
+            Ok(292 + 46)
+        );
+        assert_eq!(
+            custom_distance(input, &settings("rows=1 columns=1 row:3=2 row:7=2")),
+            Ok(292 + 36)
+        );
+        assert!(custom_distance(input, &settings("depth=2")).is_err());
+        assert!(custom_distance(input, &settings("row:x=2")).is_err());
+    }
 }
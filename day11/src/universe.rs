@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::expansion::Expansion;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Metric {
+    Manhattan,
+    Chebyshev,
+    Euclidean,
+}
+
+// Manhattan and Chebyshev distances are whole numbers of tiles, and kept exact however far
+// the universe expands. Only Euclidean ones need a float.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum Distance {
+    Exact(u128),
+    Approximate(f64),
+}
+
+impl Distance {
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Distance::Exact(d) => d as f64,
+            Distance::Approximate(d) => d,
+        }
+    }
+}
+
+impl fmt::Display for Distance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Distance::Exact(d) => write!(f, "{}", d),
+            Distance::Approximate(d) => write!(f, "{}", d),
+        }
+    }
+}
+
+impl Metric {
+    pub fn from_str(input: &str) -> Result<Self, String> {
+        match input {
+            "manhattan" => Ok(Metric::Manhattan),
+            "chebyshev" => Ok(Metric::Chebyshev),
+            "euclidean" => Ok(Metric::Euclidean),
+            _ => Err(format!("Unknown metric {}", input)),
+        }
+    }
+
+    pub fn distance(self, a: (u128, u128), b: (u128, u128)) -> Distance {
+        let rows = a.0.abs_diff(b.0);
+        let columns = a.1.abs_diff(b.1);
+        match self {
+            Metric::Manhattan => Distance::Exact(rows + columns),
+            Metric::Chebyshev => Distance::Exact(rows.max(columns)),
+            Metric::Euclidean => Distance::Approximate((rows as f64).hypot(columns as f64)),
+        }
+    }
+}
+
+// Galaxies are numbered from 1 in reading order, as in the puzzle.
+pub struct Universe {
+    galaxies: Vec<(u128, u128)>,
+}
+
+impl Universe {
+    pub fn new(input: &str, expansion: &Expansion) -> Self {
+        Universe {
+            galaxies: expansion.galaxies(input),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.galaxies.len()
+    }
+
+    pub fn galaxy(&self, number: usize) -> Option<(u128, u128)> {
+        self.galaxies.get(number.checked_sub(1)?).copied()
+    }
+
+    pub fn distance(&self, a: usize, b: usize, metric: Metric) -> Option<Distance> {
+        Some(metric.distance(self.galaxy(a)?, self.galaxy(b)?))
+    }
+
+    fn others(
+        &self,
+        number: usize,
+        metric: Metric,
+    ) -> impl Iterator<Item = (usize, Distance)> + '_ {
+        let galaxy = self.galaxy(number);
+        (1..=self.len())
+            .filter(move |other| *other != number)
+            .filter_map(move |other| Some((other, metric.distance(galaxy?, self.galaxy(other)?))))
+    }
+
+    // Ties go to the galaxy that comes first.
+    pub fn nearest(&self, number: usize, metric: Metric) -> Option<(usize, Distance)> {
+        self.others(number, metric)
+            .reduce(|best, other| if other.1 < best.1 { other } else { best })
+    }
+
+    pub fn farthest(&self, number: usize, metric: Metric) -> Option<(usize, Distance)> {
+        self.others(number, metric)
+            .reduce(|best, other| if other.1 > best.1 { other } else { best })
+    }
+
+    // Counts the pairs in each bucket of `width`, keyed by the bucket's index so that
+    // fractional widths don't merge buckets. Bucket `i` starts at `i * width`.
+    pub fn histogram(&self, metric: Metric, width: f64) -> BTreeMap<u64, usize> {
+        let mut buckets = BTreeMap::new();
+        for (i, a) in self.galaxies.iter().enumerate() {
+            for b in &self.galaxies[i + 1..] {
+                let bucket = (metric.distance(*a, *b).as_f64() / width).floor() as u64;
+                *buckets.entry(bucket).or_insert(0) += 1;
+            }
+        }
+        buckets
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn universe() -> Universe {
+        let input = include_str!("../test_files/part_one_and_two.txt");
+        Universe::new(input, &Expansion::uniform(2))
+    }
+
+    #[test]
+    fn test_puzzle_pairs() {
+        let universe = universe();
+        assert_eq!(universe.len(), 9);
+        assert_eq!(
+            universe.distance(5, 9, Metric::Manhattan),
+            Some(Distance::Exact(9))
+        );
+        assert_eq!(
+            universe.distance(1, 7, Metric::Manhattan),
+            Some(Distance::Exact(15))
+        );
+        assert_eq!(
+            universe.distance(3, 6, Metric::Manhattan),
+            Some(Distance::Exact(17))
+        );
+        assert_eq!(
+            universe.distance(8, 9, Metric::Manhattan),
+            Some(Distance::Exact(5))
+        );
+        assert_eq!(universe.distance(0, 9, Metric::Manhattan), None);
+        assert_eq!(universe.distance(1, 10, Metric::Manhattan), None);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let universe = universe();
+        // Galaxy 5 is 4 rows and 5 columns from galaxy 9 once expanded.
+        assert_eq!(
+            universe.distance(5, 9, Metric::Chebyshev),
+            Some(Distance::Exact(5))
+        );
+        assert_eq!(
+            universe.distance(5, 9, Metric::Euclidean),
+            Some(Distance::Approximate(41f64.sqrt()))
+        );
+    }
+
+    #[test]
+    fn test_exact_far_apart() {
+        let input = include_str!("../test_files/part_one_and_two.txt");
+        let expansion = Expansion::uniform(10u128.pow(17) + 1);
+        let universe = Universe::new(input, &expansion);
+        let mut total = 0;
+        for a in 1..=universe.len() {
+            for b in a + 1..=universe.len() {
+                match universe.distance(a, b, Metric::Manhattan) {
+                    Some(Distance::Exact(d)) => total += d,
+                    d => panic!("Expected an exact distance, got {:?}", d),
+                }
+            }
+        }
+        assert_eq!(total, crate::total_distance(input, &expansion));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let universe = universe();
+        assert_eq!(
+            universe.nearest(8, Metric::Manhattan),
+            Some((9, Distance::Exact(5)))
+        );
+        // Galaxies 6 and 7 are both 15 away from galaxy 1.
+        assert_eq!(
+            universe.farthest(1, Metric::Manhattan),
+            Some((6, Distance::Exact(15)))
+        );
+        assert_eq!(universe.nearest(10, Metric::Manhattan), None);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let universe = universe();
+        let histogram = universe.histogram(Metric::Manhattan, 1.0);
+        assert_eq!(histogram.values().sum::<usize>(), 36);
+        assert_eq!(
+            histogram
+                .iter()
+                .map(|(d, n)| *d as usize * n)
+                .sum::<usize>(),
+            374
+        );
+        assert_eq!(
+            universe.histogram(Metric::Manhattan, 100.0).get(&0),
+            Some(&36)
+        );
+    }
+
+    #[test]
+    fn test_fractional_width() {
+        let universe = Universe::new("#..\n...\n..#\n#..", &Expansion::uniform(1));
+        // The distances are sqrt(5), 2 * sqrt(2) and 3, starting buckets at 2, 2.5 and 3.
+        let histogram = universe.histogram(Metric::Euclidean, 0.5);
+        assert_eq!(histogram, BTreeMap::from([(4, 1), (5, 1), (6, 1)]));
+    }
+}
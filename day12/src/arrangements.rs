@@ -0,0 +1,203 @@
+use rand::Rng;
+
+use crate::{Condition, Row};
+
+// `ways[i][j]` counts the arrangements of the springs from `i` on that make up the groups
+// from `j` on. A group starting at `i` must be followed by a working spring or the end of
+// the row, so placing one skips past that spring too.
+pub struct Arrangements<'a> {
+    row: &'a Row,
+    // `run[i]` is how many springs from `i` on could all be broken.
+    run: Vec<usize>,
+    ways: Vec<Vec<u128>>,
+}
+
+impl<'a> Arrangements<'a> {
+    pub fn new(row: &'a Row) -> Self {
+        let springs = &row.springs;
+        let groups = &row.groups;
+        let n = springs.len();
+
+        let mut run = vec![0; n + 1];
+        for i in (0..n).rev() {
+            if springs[i] != Condition::Working {
+                run[i] = run[i + 1] + 1;
+            }
+        }
+
+        let mut arrangements = Arrangements {
+            row,
+            run,
+            ways: vec![vec![0u128; groups.len() + 1]; n + 2],
+        };
+        arrangements.ways[n][groups.len()] = 1;
+        arrangements.ways[n + 1][groups.len()] = 1;
+        for i in (0..n).rev() {
+            for j in (0..=groups.len()).rev() {
+                let mut total = arrangements.skipping(i, j);
+                total += arrangements.placing(i, j).map_or(0, |(_, ways)| ways);
+                arrangements.ways[i][j] = total;
+            }
+        }
+        arrangements
+    }
+
+    // The arrangements with a working spring at `i`.
+    fn skipping(&self, i: usize, j: usize) -> u128 {
+        if self.row.springs[i] == Condition::Broken {
+            0
+        } else {
+            self.ways[i + 1][j]
+        }
+    }
+
+    // The length of group `j` and the arrangements that start it at `i`, if it fits there.
+    fn placing(&self, i: usize, j: usize) -> Option<(usize, u128)> {
+        let group = *self.row.groups.get(j)? as usize;
+        let fits =
+            self.run[i] >= group && self.row.springs.get(i + group) != Some(&Condition::Broken);
+        fits.then(|| (group, self.ways[i + group + 1][j + 1]))
+    }
+
+    pub fn count(&self) -> u128 {
+        self.ways[0][0]
+    }
+
+    // Arrangements are ordered as their text, so `#` comes before `.` and a group started at
+    // a spring sorts before a working one. Each step takes the first branch that still has
+    // `k` arrangements left in it.
+    pub fn nth(&self, mut k: u128) -> Option<Vec<Condition>> {
+        if k >= self.count() {
+            return None;
+        }
+        let n = self.row.springs.len();
+        let mut springs = Vec::with_capacity(n);
+        let (mut i, mut j) = (0, 0);
+        while i < n {
+            if let Some((group, ways)) = self.placing(i, j) {
+                if k < ways {
+                    springs.extend(std::iter::repeat_n(Condition::Broken, group));
+                    if i + group < n {
+                        springs.push(Condition::Working);
+                    }
+                    i += group + 1;
+                    j += 1;
+                    continue;
+                }
+                k -= ways;
+            }
+            springs.push(Condition::Working);
+            i += 1;
+        }
+        Some(springs)
+    }
+
+    // Lazily walks every arrangement in order, one descent through the table each.
+    pub fn iter(&self) -> impl Iterator<Item = Vec<Condition>> + '_ {
+        (0..self.count()).map_while(|k| self.nth(k))
+    }
+
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<Vec<Condition>> {
+        match self.count() {
+            0 => None,
+            count => self.nth(rng.gen_range(0..count)),
+        }
+    }
+}
+
+pub fn count(row: &Row) -> u128 {
+    Arrangements::new(row).count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::to_string;
+
+    // Tries every assignment of the unknown springs.
+    fn brute_force(row: &Row) -> u128 {
+        let unknown: Vec<_> = (0..row.springs.len())
+            .filter(|i| row.springs[*i] == Condition::Unknown)
+            .collect();
+        (0..1u32 << unknown.len())
+            .filter(|mask| {
+                let mut springs = row.springs.clone();
+                for (bit, i) in unknown.iter().enumerate() {
+                    springs[*i] = if mask >> bit & 1 == 1 {
+                        Condition::Broken
+                    } else {
+                        Condition::Working
+                    };
+                }
+                let groups: Vec<u32> = springs
+                    .split(|c| *c == Condition::Working)
+                    .filter(|run| !run.is_empty())
+                    .map(|run| run.len() as u32)
+                    .collect();
+                groups == row.groups
+            })
+            .count() as u128
+    }
+
+    #[test]
+    fn test_examples() {
+        let counts: Vec<_> = include_str!("../test_files/part_one_and_two.txt")
+            .lines()
+            .map(|line| count(&Row::from_string(line)))
+            .collect();
+        assert_eq!(counts, vec![1, 4, 1, 1, 4, 10]);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(12);
+        for _ in 0..500 {
+            let length = rng.gen_range(1..=12);
+            let springs: String = (0..length)
+                .map(|_| ['.', '#', '?'][rng.gen_range(0..3)])
+                .collect();
+            let groups: Vec<_> = (0..rng.gen_range(1..=4))
+                .map(|_| rng.gen_range(1..=4).to_string())
+                .collect();
+            let row = Row::from_string(&format!("{} {}", springs, groups.join(",")));
+            assert_eq!(count(&row), brute_force(&row), "{:?}", row);
+        }
+    }
+
+    #[test]
+    fn test_enumeration() {
+        let row = Row::from_string("?###???????? 3,2,1");
+        let arrangements = Arrangements::new(&row);
+        let all: Vec<_> = arrangements.iter().map(|s| to_string(&s)).collect();
+        assert_eq!(all.len(), 10);
+        assert_eq!(all[0], ".###.##.#...");
+        assert_eq!(all[9], ".###....##.#");
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(arrangements.nth(10), None);
+
+        let none = Row::from_string("#.# 3");
+        assert_eq!(Arrangements::new(&none).iter().count(), 0);
+        assert_eq!(
+            Arrangements::new(&none).sample(&mut StdRng::seed_from_u64(0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sample_unfolded() {
+        // Far too many arrangements to list, but each sample is a single walk.
+        let row = Row::from_string("????????????????????????????????????????????? 1,1,1,1,1,1,1,1");
+        let arrangements = Arrangements::new(&row);
+        assert!(arrangements.count() > 1_000_000);
+        let mut rng = StdRng::seed_from_u64(46);
+        for _ in 0..100 {
+            let springs = arrangements.sample(&mut rng).unwrap();
+            let line = format!("{} 1,1,1,1,1,1,1,1", to_string(&springs));
+            assert_eq!(count(&Row::from_string(&line)), 1);
+        }
+    }
+}
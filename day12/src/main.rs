@@ -1,7 +1,12 @@
-use std::collections::HashMap;
+use std::fmt::Write;
 
 use rayon::prelude::*;
 
+use arrangements::Arrangements;
+
+mod arrangements;
+mod report;
+
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
 enum Condition {
     Unknown,
@@ -18,6 +23,18 @@ impl Condition {
             _ => panic!("Invalid character found in input"),
         }
     }
+
+    fn to_char(self) -> char {
+        match self {
+            Self::Working => '.',
+            Self::Broken => '#',
+            Self::Unknown => '?',
+        }
+    }
+}
+
+fn to_string(springs: &[Condition]) -> String {
+    springs.iter().map(|c| c.to_char()).collect()
 }
 
 #[derive(Clone, Debug)]
@@ -46,231 +63,150 @@ impl Row {
                 .collect(),
         }
     }
-}
-
-fn get_number_of_arrangements(row: Row, is_reading_group: Option<bool>) -> u32 {
-    let mut row = row;
 
-    if row.groups.is_empty() && row.springs.is_empty() {
-        return 1;
-    }
-    match row.springs.pop() {
-        Some(c) => match c {
-            Condition::Unknown => {
-                let mut row_broken = row.clone();
-                row_broken.springs.push(Condition::Broken);
-                row.springs.push(Condition::Working);
-                get_number_of_arrangements(row_broken, is_reading_group)
-                    + get_number_of_arrangements(row, is_reading_group)
-            }
-            Condition::Broken => {
-                match row.groups.last() {
-                    None => return 0,
-                    Some(v) => {
-                        if v == &0 {
-                            return 0;
-                        }
-                    }
-                }
-                *row.groups.last_mut().unwrap() -= 1;
-                get_number_of_arrangements(row, Some(true))
-            }
-            Condition::Working => {
-                // We must have a 0 at the start of the groups list, otherwise this path is invalid.
-                if is_reading_group.is_none() {
-                    get_number_of_arrangements(row, Some(false))
-                } else if is_reading_group.unwrap() && row.groups.last().unwrap() == &0 {
-                    row.groups.pop();
-                    get_number_of_arrangements(row, Some(false))
-                } else if !is_reading_group.unwrap() {
-                    get_number_of_arrangements(row, Some(false))
-                } else {
-                    0
-                }
-            }
-        },
-        None => {
-            if row.groups == vec![0] {
-                1
-            } else {
-                0
+    // Repeats the row `times` times, joining the copies of the springs with `separator`.
+    fn unfold(&self, times: usize, separator: Condition) -> Self {
+        let mut springs = Vec::with_capacity((self.springs.len() + 1) * times);
+        for i in 0..times {
+            if i > 0 {
+                springs.push(separator);
             }
+            springs.extend_from_slice(&self.springs);
+        }
+        Row {
+            springs,
+            groups: self.groups.repeat(times),
         }
     }
 }
 
-fn part_one(input: &str) -> u32 {
+// The number of arrangements of the row unfolded once, twice, up to `max_times`.
+fn growth(row: &Row, max_times: usize, separator: Condition) -> Vec<u128> {
+    (1..=max_times)
+        .map(|times| arrangements::count(&row.unfold(times, separator)))
+        .collect()
+}
+
+fn part_one(input: &str) -> u128 {
     input
         .lines()
         .map(Row::from_string)
-        .map(|row| get_number_of_arrangements(row, None))
+        .map(|row| arrangements::count(&row))
         .sum()
 }
 
-fn get_ways_to_fill_chunk(
-    conditions: Vec<Condition>,
-    groups: Vec<u32>,
-    is_grouping: bool,
-) -> Vec<Vec<u32>> {
-    // dbg!(conditions, groups, is_grouping);
-    let mut conditions = conditions;
-    let mut groups = groups;
-    let out = if conditions.is_empty() {
-        if is_grouping {
-            if groups.last().unwrap() == &0 {
-                groups.pop();
-                return vec![groups];
-            } else {
-                return vec![];
-            }
-        } else {
-            return vec![groups];
-        }
-    } else {
-        match conditions.last().unwrap() {
-            Condition::Unknown => {
-                if groups.is_empty() {
-                    conditions.pop();
-                    return get_ways_to_fill_chunk(conditions, groups, false);
-                }
-                if is_grouping {
-                    if groups.last().unwrap() == &0 {
-                        groups.pop();
-                        conditions.pop();
-                        get_ways_to_fill_chunk(conditions, groups, false)
-                    } else {
-                        conditions.pop();
-                        conditions.push(Condition::Broken);
-                        get_ways_to_fill_chunk(conditions, groups, false)
-                    }
-                } else {
-                    conditions.pop();
-                    let mut broken_conditions = conditions.clone();
-                    broken_conditions.push(Condition::Broken);
-                    let mut broken_groups =
-                        get_ways_to_fill_chunk(broken_conditions, groups.clone(), false);
-                    let working_groups = get_ways_to_fill_chunk(conditions, groups, false);
-                    broken_groups.extend(working_groups);
-                    broken_groups
-                }
-            }
-            Condition::Broken => {
-                if groups.is_empty() {
-                    return vec![];
-                }
-                if groups.last().unwrap() == &0 {
-                    return vec![];
-                }
-                *groups.last_mut().unwrap() -= 1;
-                conditions.pop();
-                get_ways_to_fill_chunk(conditions, groups, true)
-            }
-            Condition::Working => unreachable!(),
-        }
+fn part_two(input: &str) -> u128 {
+    input
+        .par_lines()
+        .map(Row::from_string)
+        .map(|row| row.unfold(5, Condition::Unknown))
+        .map(|row| arrangements::count(&row))
+        .sum()
+}
+
+// Lists the first arrangements of a row (numbered from 1), the k-th one from 0, or a random
+// one.
+fn show_arrangements(input: &str, args: &[String]) -> Result<Vec<String>, String> {
+    let line = args
+        .get(1)
+        .and_then(|n| n.parse::<usize>().ok())
+        .and_then(|n| input.lines().nth(n.checked_sub(1)?))
+        .ok_or("Expected the number of a row in the input")?;
+    let row = Row::from_string(line);
+    let arrangements = Arrangements::new(&row);
+    let number = |default| {
+        args.get(2).map_or(Ok(default), |n| {
+            n.parse().map_err(|_| format!("Invalid number {}", n))
+        })
     };
 
-    // dbg!(out)
-    out
+    let springs = match args[0].as_str() {
+        "list" => arrangements.iter().take(number(20)? as usize).collect(),
+        "nth" => arrangements.nth(number(0)?).into_iter().collect(),
+        _ => arrangements
+            .sample(&mut rand::thread_rng())
+            .into_iter()
+            .collect::<Vec<_>>(),
+    };
+    Ok(springs.iter().map(|s| to_string(s)).collect())
 }
 
-fn get_number_of_arrangements_faster(row: Row) -> usize {
-    let chunks: Vec<Vec<&Condition>> = row
-        .springs
-        .iter()
-        .fold(vec![], |mut acc, c: &Condition| {
-            if c == &Condition::Working || acc.is_empty() {
-                acc.push(vec![])
-            }
-            if c != &Condition::Working {
-                acc.last_mut().unwrap().push(c);
-            }
-            acc
-        })
+fn chart_growth(input: &str, args: &[String]) -> Result<String, String> {
+    let line = args
+        .get(1)
+        .and_then(|n| n.parse::<usize>().ok())
+        .and_then(|n| input.lines().nth(n.checked_sub(1)?))
+        .ok_or("Expected the number of a row in the input")?;
+    let max_times = match args.get(2).map(|n| n.parse()) {
+        None => 5,
+        Some(Ok(max_times)) => max_times,
+        Some(Err(_)) => return Err(format!("Invalid unfold factor {}", args[2])),
+    };
+    let separator = match args.get(3).map(|s| s.chars().collect::<Vec<_>>()) {
+        None => Condition::Unknown,
+        Some(c) if c.len() == 1 && ".#?".contains(c[0]) => Condition::from_char(c[0]),
+        Some(_) => return Err(format!("Invalid separator {}", args[3])),
+    };
+
+    let mut out = String::new();
+    let mut previous = None;
+    for (i, count) in growth(&Row::from_string(line), max_times, separator)
         .into_iter()
-        .rev()
-        .collect();
-    // if chunks.len() == 1 {
-    //     return get_number_of_arrangements(row, None) as usize;
-    // }
+        .enumerate()
+    {
+        let ratio = match previous {
+            Some(previous) if previous > 0 => format!("{:.3}", count as f64 / previous as f64),
+            _ => "-".to_string(),
+        };
+        writeln!(out, "{:>3} {:>40} {:>10}", i + 1, count, ratio).unwrap();
+        previous = Some(count);
+    }
+    Ok(out)
+}
 
-    // dbg!(&row, &chunks);
+fn main() {
+    let input = include_str!("../input.txt");
 
-    let mut prev_groups_left: Vec<(Vec<u32>, usize)> = vec![(row.groups, 1)];
-    for chunk in chunks {
-        // dbg!(&prev_groups_left);
-        let mut groups_left = Vec::new();
-        for group in prev_groups_left.iter() {
-            if group.0.is_empty() {
-                // This group is no longer valid.
-                continue;
-            }
-            if chunk.len() as u32 == *group.0.last().unwrap() {
-                // The head of the group is the same size as the current chunk, so we're good.
-                let mut group = group.to_owned();
-                group.0.pop();
-                groups_left.push(group);
-                continue;
-            }
-            let groups = get_ways_to_fill_chunk(
-                chunk.iter().copied().copied().collect(),
-                group.0.to_owned(),
-                false,
-            );
-            for g in groups {
-                groups_left.push((g, group.1));
-            }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // `growth <row> [max factor] [separator]` charts the count as the row is unfolded.
+    if args.first().map(String::as_str) == Some("growth") {
+        match chart_growth(input, &args) {
+            Ok(chart) => print!("{}", chart),
+            Err(e) => eprintln!("{}", e),
         }
-
-        prev_groups_left.clear();
-        let mut hashmap = HashMap::new();
-        for group in groups_left {
-            hashmap
-                .entry(group.0)
-                .and_modify(|e| *e += group.1)
-                .or_insert(group.1);
+        return;
+    }
+    // `list <row> [count]`, `nth <row> <k>` and `sample <row>` show concrete arrangements.
+    if matches!(
+        args.first().map(String::as_str),
+        Some("list" | "nth" | "sample")
+    ) {
+        match show_arrangements(input, &args) {
+            Ok(lines) => lines.iter().for_each(|line| println!("{}", line)),
+            Err(e) => eprintln!("{}", e),
         }
-        prev_groups_left.append(&mut hashmap.into_iter().collect());
+        return;
     }
-
-    let mut combined = 1;
-    for group in prev_groups_left {
-        if group.0.is_empty() {
-            combined *= group.1;
+    // `report [table|json] [threshold]` lists the count of every row unfolded for part two,
+    // flagging those above the threshold.
+    if args.first().map(String::as_str) == Some("report") {
+        let threshold = match args.get(2).map(|t| t.parse()) {
+            None => None,
+            Some(Ok(threshold)) => Some(threshold),
+            Some(Err(_)) => {
+                eprintln!("Invalid threshold {}", args[2]);
+                return;
+            }
+        };
+        let reports = report::report(input, 5, threshold);
+        match args.get(1).map(String::as_str) {
+            None | Some("table") => print!("{}", report::to_table(&reports)),
+            Some("json") => println!("{}", report::to_json(&reports)),
+            Some(format) => eprintln!("Unknown report format {}", format),
         }
+        return;
     }
 
-    combined
-}
-
-fn part_two(input: &str) -> usize {
-    input
-        .par_lines()
-        .map(Row::from_string)
-        .map(|row| Row {
-            springs: std::iter::repeat(
-                row.springs
-                    .iter()
-                    .chain(std::iter::once(&Condition::Unknown)),
-            )
-            .flatten()
-            .take(5 * row.springs.len() + 4)
-            .copied()
-            .collect(),
-            groups: std::iter::repeat(row.groups.iter())
-                .take(5)
-                .flatten()
-                .copied()
-                .collect(),
-        })
-        .map(get_number_of_arrangements_faster)
-        // .map(|row| get_number_of_arrangements(row, None))
-        .map(|c| dbg!(c))
-        // .sum::<u32>() as usize
-        .sum()
-}
-
-fn main() {
-    let input = include_str!("../input.txt");
     println!("Part one: {}", part_one(input));
     println!("Part two: {}", part_two(input));
 }
@@ -290,4 +226,30 @@ mod test {
         let input = include_str!("../test_files/part_one_and_two.txt");
         assert_eq!(part_two(input), 525152)
     }
+
+    #[test]
+    fn test_unfold() {
+        let row = Row::from_string(".# 1");
+        let unfolded = row.unfold(3, Condition::Unknown);
+        assert_eq!(to_string(&unfolded.springs), ".#?.#?.#");
+        assert_eq!(unfolded.groups, vec![1, 1, 1]);
+        assert_eq!(
+            to_string(&row.unfold(2, Condition::Working).springs),
+            ".#..#"
+        );
+        assert!(row.unfold(0, Condition::Unknown).springs.is_empty());
+    }
+
+    #[test]
+    fn test_growth() {
+        let row = Row::from_string("????.######..#####. 1,6,5");
+        assert_eq!(
+            growth(&row, 5, Condition::Unknown),
+            vec![4, 20, 100, 500, 2500]
+        );
+        // A broken separator merges the groups either side of it, so nothing fits.
+        let row = Row::from_string("?# 1");
+        assert_eq!(growth(&row, 3, Condition::Broken), vec![1, 0, 0]);
+        assert_eq!(growth(&row, 3, Condition::Working), vec![1, 1, 1]);
+    }
 }
@@ -0,0 +1,98 @@
+use std::fmt::Write;
+
+use rayon::prelude::*;
+use serde_json::json;
+
+use crate::{arrangements, Condition, Row};
+
+pub struct RowReport {
+    pub text: String,
+    pub unfolded_length: usize,
+    pub count: u128,
+    // Set when the count is above the threshold the report was made with.
+    pub flagged: bool,
+}
+
+pub fn report(input: &str, times: usize, threshold: Option<u128>) -> Vec<RowReport> {
+    input
+        .par_lines()
+        .map(|line| {
+            let row = Row::from_string(line).unfold(times, Condition::Unknown);
+            let count = arrangements::count(&row);
+            RowReport {
+                text: line.trim().to_string(),
+                unfolded_length: row.springs.len(),
+                count,
+                flagged: threshold.is_some_and(|threshold| count > threshold),
+            }
+        })
+        .collect()
+}
+
+pub fn to_table(reports: &[RowReport]) -> String {
+    let width = reports.iter().map(|r| r.text.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    writeln!(out, "{:<width$} {:>8} {:>40}", "row", "length", "count").unwrap();
+    for report in reports {
+        writeln!(
+            out,
+            "{:<width$} {:>8} {:>40}{}",
+            report.text,
+            report.unfolded_length,
+            report.count,
+            if report.flagged { " !" } else { "" }
+        )
+        .unwrap();
+    }
+    out
+}
+
+pub fn to_json(reports: &[RowReport]) -> String {
+    let rows: Vec<_> = reports
+        .iter()
+        .map(|report| {
+            json!({
+                "row": report.text,
+                "unfolded_length": report.unfolded_length,
+                // Counts can pass u64::MAX, which serde_json numbers only hold with a feature that
+                // would change them for the whole workspace.
+                "count": report.count.to_string(),
+                "flagged": report.flagged,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&rows).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let input = include_str!("../test_files/part_one_and_two.txt");
+        let reports = report(input, 5, Some(10_000));
+        let counts: Vec<_> = reports.iter().map(|r| r.count).collect();
+        assert_eq!(counts, vec![1, 16384, 1, 16, 2500, 506250]);
+        let flagged: Vec<_> = reports.iter().map(|r| r.flagged).collect();
+        assert_eq!(flagged, vec![false, true, false, false, false, true]);
+        assert_eq!(reports[0].text, "???.### 1,1,3");
+        assert_eq!(reports[0].unfolded_length, 39);
+        assert!(to_table(&reports).lines().nth(2).unwrap().ends_with(" !"));
+    }
+
+    #[test]
+    fn test_json() {
+        let reports = report("???.### 1,1,3", 1, None);
+        let json: serde_json::Value = serde_json::from_str(&to_json(&reports)).unwrap();
+        assert_eq!(
+            json,
+            json!([{
+                "row": "???.### 1,1,3",
+                "unfolded_length": 7,
+                "count": "1",
+                "flagged": false,
+            }])
+        );
+    }
+}
@@ -1,105 +1,49 @@
-fn part_one(input: &str) -> usize {
-    let mut total = 0;
-    for board in input.split("\r\n\r\n") {
-        let board: Vec<Vec<_>> = board.lines().map(|l| l.chars().collect()).collect();
-        let rows = board.len();
-        let cols = board.first().unwrap().len();
-
-        let mut found = false;
+mod pattern;
 
-        for row in 1..rows {
-            let max_check = (2 * row - 1).min(rows - 1);
-            let min_check = 0.max(row - (max_check - row) - 1);
-            if (min_check..row).all(|i| {
-                let offset = 2 * (row - i) - 1;
-                (0..cols).all(|j| board[i][j] == board[i + offset][j])
-            }) {
-                total += row * 100;
-                found = true;
-                break;
-            }
-        }
-        if found {
-            continue;
-        }
+// The first mirror of each pattern that needs exactly `smudges` cells fixed.
+fn summarise(input: &str, smudges: usize) -> usize {
+    pattern::patterns(input)
+        .iter()
+        .filter_map(|pattern| pattern.reflections(smudges).first().map(|r| r.summary()))
+        .sum()
+}
 
-        for col in 1..cols {
-            let max_check = (2 * col - 1).min(cols - 1);
-            let min_check = 0.max(col - (max_check - col) - 1);
-            if (min_check..col).all(|i| {
-                let offset = 2 * (col - i) - 1;
-                (0..rows).all(|j| board[j][i] == board[j][i + offset])
-            }) {
-                total += col;
-                found = true;
-                break;
-            }
-        }
-        if found {
-            continue;
-        }
-    }
-    total
+fn part_one(input: &str) -> usize {
+    summarise(input, 0)
 }
 
 fn part_two(input: &str) -> usize {
-    let mut total = 0;
-    for board in input.split("\r\n\r\n") {
-        let board: Vec<Vec<_>> = board.lines().map(|l| l.chars().collect()).collect();
-        let rows = board.len();
-        let cols = board.first().unwrap().len();
+    summarise(input, 1)
+}
 
-        let mut found = false;
+fn main() {
+    let input = include_str!("../input.txt");
 
-        for row in 1..rows {
-            let max_check = (2 * row - 1).min(rows - 1);
-            let min_check = 0.max(row - (max_check - row) - 1);
-            if (min_check..row)
-                .map(|i| {
-                    let offset = 2 * (row - i) - 1;
-                    (0..cols)
-                        .filter(|j| board[i][*j] != board[i + offset][*j])
-                        .count()
-                })
-                .sum::<usize>()
-                == 1
-            {
-                total += row * 100;
-                found = true;
-                break;
+    // `reflections <k> [--transposed]` lists every mirror needing exactly k smudges fixed,
+    // optionally with each pattern turned on its side.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("reflections") {
+        let Some(Ok(smudges)) = args.get(1).map(|k| k.parse()) else {
+            eprintln!("Expected a number of smudges");
+            return;
+        };
+        let transposed = args.get(2).map(String::as_str) == Some("--transposed");
+        for (i, mut pattern) in pattern::patterns(input).into_iter().enumerate() {
+            if transposed {
+                pattern = pattern.transposed();
             }
-        }
-        if found {
-            continue;
-        }
-
-        for col in 1..cols {
-            let max_check = (2 * col - 1).min(cols - 1);
-            let min_check = 0.max(col - (max_check - col) - 1);
-            if (min_check..col)
-                .map(|i| {
-                    let offset = 2 * (col - i) - 1;
-                    (0..rows)
-                        .filter(|j| board[*j][i] != board[*j][i + offset])
-                        .count()
-                })
-                .sum::<usize>()
-                == 1
-            {
-                total += col;
-                found = true;
-                break;
+            for reflection in pattern.reflections(smudges) {
+                println!(
+                    "Pattern {}: {:?} mirror after {}, mismatches {:?}",
+                    i + 1,
+                    reflection.axis,
+                    reflection.position,
+                    reflection.mismatches
+                );
             }
         }
-        if found {
-            continue;
-        }
+        return;
     }
-    total
-}
-
-fn main() {
-    let input = include_str!("../input.txt");
     println!("Part one: {}", part_one(input));
     println!("Part two: {}", part_two(input));
 }
@@ -0,0 +1,250 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    // A mirror between two rows.
+    Horizontal,
+    // A mirror between two columns.
+    Vertical,
+}
+
+// A cell that differs from its mirror image, paired with it, both as (row, column).
+pub type Mismatch = ((usize, usize), (usize, usize));
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Reflection {
+    pub axis: Axis,
+    // How many rows or columns lie before the mirror.
+    pub position: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Reflection {
+    pub fn summary(&self) -> usize {
+        match self.axis {
+            Axis::Horizontal => 100 * self.position,
+            Axis::Vertical => self.position,
+        }
+    }
+}
+
+// Each row and column is a bitmask of its rocks, split into 64 bit words so patterns can be
+// any size. Both are built once, which makes transposing a swap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    rows: Vec<Vec<u64>>,
+    columns: Vec<Vec<u64>>,
+}
+
+fn set_bit(line: &mut [u64], bit: usize) {
+    line[bit / 64] |= 1 << (bit % 64);
+}
+
+// The cells where two lines differ, by index along the line.
+fn differences<'a>(a: &'a [u64], b: &'a [u64]) -> impl Iterator<Item = usize> + 'a {
+    a.iter().zip(b).enumerate().flat_map(|(word, (a, b))| {
+        let mut xor = a ^ b;
+        std::iter::from_fn(move || {
+            (xor != 0).then(|| {
+                let bit = xor.trailing_zeros() as usize;
+                xor &= xor - 1;
+                word * 64 + bit
+            })
+        })
+    })
+}
+
+impl Pattern {
+    pub fn from_str(input: &str) -> Self {
+        let lines: Vec<&str> = input.lines().map(str::trim).collect();
+        let width = lines.first().map_or(0, |line| line.len());
+        if lines.iter().any(|line| line.len() != width) {
+            panic!("Pattern rows must all be the same length");
+        }
+
+        let words = |length: usize| vec![0; length.div_ceil(64)];
+        let mut rows = vec![words(width); lines.len()];
+        let mut columns = vec![words(lines.len()); width];
+        for (i, line) in lines.iter().enumerate() {
+            for (j, c) in line.chars().enumerate() {
+                match c {
+                    '#' => {
+                        set_bit(&mut rows[i], j);
+                        set_bit(&mut columns[j], i);
+                    }
+                    '.' => {}
+                    _ => panic!("Invalid character found in input"),
+                }
+            }
+        }
+        Pattern { rows, columns }
+    }
+
+    pub fn transposed(self) -> Self {
+        Pattern {
+            rows: self.columns,
+            columns: self.rows,
+        }
+    }
+
+    // Mirrors between lines with exactly `smudges` cells differing from their reflection, as
+    // (line, index along it) pairs. The popcount of each XOR rules most candidates out before
+    // any cell is looked at.
+    fn mirrors(lines: &[Vec<u64>], smudges: usize) -> Vec<(usize, Vec<Mismatch>)> {
+        let rows = lines.len();
+        (1..rows)
+            .filter_map(|position| {
+                let pairs = (0..position).rev().zip(position..rows);
+                let mut count = 0;
+                for (above, below) in pairs.clone() {
+                    count += lines[above]
+                        .iter()
+                        .zip(&lines[below])
+                        .map(|(a, b)| (a ^ b).count_ones() as usize)
+                        .sum::<usize>();
+                    if count > smudges {
+                        return None;
+                    }
+                }
+                if count != smudges {
+                    return None;
+                }
+                let mismatches = pairs
+                    .flat_map(|(above, below)| {
+                        differences(&lines[above], &lines[below])
+                            .map(move |j| ((above, j), (below, j)))
+                    })
+                    .collect();
+                Some((position, mismatches))
+            })
+            .collect()
+    }
+
+    // Every mirror, horizontal ones first, that needs exactly `smudges` cells fixed.
+    pub fn reflections(&self, smudges: usize) -> Vec<Reflection> {
+        let horizontal =
+            Self::mirrors(&self.rows, smudges)
+                .into_iter()
+                .map(|(position, mismatches)| Reflection {
+                    axis: Axis::Horizontal,
+                    position,
+                    mismatches,
+                });
+        let vertical =
+            Self::mirrors(&self.columns, smudges)
+                .into_iter()
+                .map(|(position, mismatches)| Reflection {
+                    axis: Axis::Vertical,
+                    position,
+                    mismatches: mismatches
+                        .into_iter()
+                        .map(|((i, j), (k, l))| ((j, i), (l, k)))
+                        .collect(),
+                });
+        horizontal.chain(vertical).collect()
+    }
+}
+
+// Patterns are separated by blank lines, whatever the line endings.
+pub fn patterns(input: &str) -> Vec<Pattern> {
+    let mut patterns = vec![];
+    let mut block = String::new();
+    for line in input.lines().chain(std::iter::once("")) {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                patterns.push(Pattern::from_str(&block));
+                block.clear();
+            }
+        } else {
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+    patterns
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reflections() {
+        let patterns = patterns(include_str!("../test_files/part_one_and_two.txt"));
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(
+            patterns[0].reflections(0),
+            vec![Reflection {
+                axis: Axis::Vertical,
+                position: 5,
+                mismatches: vec![],
+            }]
+        );
+        assert_eq!(
+            patterns[0].reflections(1),
+            vec![Reflection {
+                axis: Axis::Horizontal,
+                position: 3,
+                mismatches: vec![((0, 0), (5, 0))],
+            }]
+        );
+        assert_eq!(patterns[1].reflections(1)[0].position, 1);
+        assert_eq!(
+            patterns[1].reflections(1)[0].mismatches,
+            vec![((0, 4), (1, 4))]
+        );
+    }
+
+    #[test]
+    fn test_transposed() {
+        let pattern = Pattern::from_str("#..\n.#.");
+        assert_eq!(
+            pattern.clone().transposed(),
+            Pattern::from_str("#.\n.#\n..")
+        );
+        assert_eq!(pattern.clone().transposed().transposed(), pattern);
+    }
+
+    #[test]
+    fn test_wide_pattern() {
+        // Mirrored between columns 70 and 71, apart from a smudge at column 90.
+        let half: String = (0..70)
+            .map(|j| if j % 3 == 0 { '#' } else { '.' })
+            .collect();
+        let mirrored: String = half.chars().rev().collect();
+        let top = format!("{}{}", half, mirrored);
+        let mut bottom: Vec<char> = format!("{}{}", mirrored, half).chars().collect();
+        bottom[90] = if bottom[90] == '#' { '.' } else { '#' };
+        let bottom: String = bottom.into_iter().collect();
+        let pattern = Pattern::from_str(&format!("{}\n{}", top, bottom));
+
+        let reflections = pattern.reflections(1);
+        assert!(reflections.contains(&Reflection {
+            axis: Axis::Vertical,
+            position: 70,
+            mismatches: vec![((1, 49), (1, 90))],
+        }));
+        assert!(pattern.reflections(0).iter().all(|r| r.position != 70));
+    }
+
+    #[test]
+    fn test_crlf() {
+        let input = "#.\r\n#.\r\n\r\n##\r\n..\r\n";
+        let patterns = patterns(input);
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].reflections(0)[0].axis, Axis::Horizontal);
+        assert_eq!(patterns[1].reflections(0)[0].axis, Axis::Vertical);
+    }
+
+    #[test]
+    fn test_many_smudges() {
+        let pattern = Pattern::from_str("#..#\n....");
+        let positions = |k| -> Vec<_> {
+            pattern
+                .reflections(k)
+                .iter()
+                .map(|r| (r.axis, r.position))
+                .collect()
+        };
+        assert_eq!(positions(0), vec![(Axis::Vertical, 2)]);
+        assert_eq!(positions(1), vec![(Axis::Vertical, 1), (Axis::Vertical, 3)]);
+        assert_eq!(positions(2), vec![(Axis::Horizontal, 1)]);
+    }
+}
@@ -1,52 +1,27 @@
+use num::{BigUint, ToPrimitive};
+
+mod race;
+mod sheet;
+
+use race::Race;
+use sheet::Sheet;
+
 fn part_one(input: &str) -> u64 {
-    let mut input = input.lines();
-    let times = input
-        .next()
-        .unwrap()
-        .strip_prefix("Time: ")
+    Sheet::from_string(input)
+        .races()
+        .iter()
+        .map(|race| race.winning_count())
+        .product::<BigUint>()
+        .to_u64()
         .unwrap()
-        .split_whitespace()
-        .map(|n| n.parse().unwrap());
-    let distances = input
-        .next()
-        .unwrap()
-        .strip_prefix("Distance: ")
-        .unwrap()
-        .split_whitespace()
-        .map(|n| n.parse().unwrap());
-
-    times
-        .zip(distances)
-        .map(|(max_time, distance)| {
-            (0..max_time)
-                .filter(|time| (max_time - time) * time > distance)
-                .count() as u64
-        })
-        .product()
 }
 
 fn part_two(input: &str) -> u128 {
-    let mut input = input.lines();
-    let max_time: u128 = input
-        .next()
+    Sheet::from_string(input)
+        .kerned_race()
+        .winning_count()
+        .to_u128()
         .unwrap()
-        .strip_prefix("Time: ")
-        .unwrap()
-        .replace(' ', "")
-        .parse()
-        .unwrap();
-    let distance: u128 = input
-        .next()
-        .unwrap()
-        .strip_prefix("Distance: ")
-        .unwrap()
-        .replace(' ', "")
-        .parse()
-        .unwrap();
-
-    (0..max_time)
-        .filter(|time| (max_time - time) * time > distance)
-        .count() as u128
 }
 
 fn main() {
@@ -54,6 +29,17 @@ fn main() {
 
     println!("Part one: {}", part_one(input));
     println!("Part two: {}", part_two(input));
+
+    let race = Sheet::from_string(input).kerned_race();
+    if let Some(range) = race.winning_hold_times() {
+        println!("Winning hold times: {}..={}", range.start(), range.end());
+    }
+    println!(
+        "Best hold time: {} (reaches {}), shortest race to beat the record: {}",
+        race.optimal_hold_time(),
+        race.best_distance(),
+        Race::min_time_to_beat(&race.distance)
+    );
 }
 
 #[cfg(test)]
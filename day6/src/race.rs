@@ -0,0 +1,153 @@
+use std::ops::RangeInclusive;
+
+use num::{BigUint, One, Zero};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Race {
+    pub time: BigUint,
+    pub distance: BigUint,
+}
+
+impl Race {
+    pub fn new(time: impl Into<BigUint>, distance: impl Into<BigUint>) -> Self {
+        Race {
+            time: time.into(),
+            distance: distance.into(),
+        }
+    }
+
+    fn distance_for(&self, hold_time: &BigUint) -> BigUint {
+        if hold_time > &self.time {
+            return BigUint::zero();
+        }
+        hold_time * (&self.time - hold_time)
+    }
+
+    fn wins(&self, hold_time: &BigUint) -> bool {
+        self.distance_for(hold_time) > self.distance
+    }
+
+    // The winning hold times are the integers strictly between the roots of
+    // t^2 - T*t + D = 0, so we only need the smallest one: the rest follows by symmetry.
+    fn first_winning_hold_time(&self) -> Option<BigUint> {
+        let four_d = &self.distance * 4u32;
+        let t_squared = &self.time * &self.time;
+        if t_squared <= four_d {
+            return None;
+        }
+        let root = (t_squared - four_d).sqrt();
+
+        // `root` is the floor of the real square root, so this lands on or just below
+        // the smaller real root of the quadratic.
+        let mut hold_time = (&self.time - root) / 2u32;
+        if !self.wins(&hold_time) {
+            hold_time += 1u32;
+        }
+        if self.wins(&hold_time) {
+            Some(hold_time)
+        } else {
+            None
+        }
+    }
+
+    pub fn winning_hold_times(&self) -> Option<RangeInclusive<BigUint>> {
+        self.first_winning_hold_time().map(|first| {
+            let last = &self.time - &first;
+            first..=last
+        })
+    }
+
+    pub fn winning_count(&self) -> BigUint {
+        match self.winning_hold_times() {
+            Some(range) => range.end() - range.start() + BigUint::one(),
+            None => BigUint::zero(),
+        }
+    }
+
+    pub fn optimal_hold_time(&self) -> BigUint {
+        &self.time / 2u32
+    }
+
+    pub fn best_distance(&self) -> BigUint {
+        self.distance_for(&self.optimal_hold_time())
+    }
+
+    // The best distance for a race of time T is floor(T/2) * ceil(T/2), so starting at twice
+    // the square root of the record leaves at most two steps to take.
+    pub fn min_time_to_beat(record: &BigUint) -> BigUint {
+        let mut time = record.sqrt() * 2u32;
+        while Race::new(time.clone(), record.clone()).best_distance() <= *record {
+            time += 1u32;
+        }
+        time
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn brute_force(time: u64, distance: u64) -> u64 {
+        (0..=time)
+            .filter(|hold| (time - hold) * hold > distance)
+            .count() as u64
+    }
+
+    #[test]
+    fn test_winning_count_matches_brute_force() {
+        for time in 0..60u64 {
+            for distance in 0..(time * time / 4 + 3) {
+                assert_eq!(
+                    Race::new(time, distance).winning_count(),
+                    BigUint::from(brute_force(time, distance)),
+                    "time {} distance {}",
+                    time,
+                    distance
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_queries() {
+        let race = Race::new(7u32, 9u32);
+        assert_eq!(
+            race.winning_hold_times(),
+            Some(BigUint::from(2u32)..=BigUint::from(5u32))
+        );
+        assert_eq!(race.optimal_hold_time(), BigUint::from(3u32));
+        assert_eq!(race.best_distance(), BigUint::from(12u32));
+        assert_eq!(
+            Race::new(30u32, 200u32)
+                .winning_hold_times()
+                .unwrap()
+                .start(),
+            &BigUint::from(11u32)
+        );
+        assert_eq!(Race::new(3u32, 2u32).winning_hold_times(), None);
+    }
+
+    #[test]
+    fn test_min_time_to_beat() {
+        for record in 0..500u64 {
+            let expected = (0..)
+                .find(|&time| (time / 2) * (time - time / 2) > record)
+                .unwrap();
+            assert_eq!(
+                Race::min_time_to_beat(&BigUint::from(record)),
+                BigUint::from(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_winning_count_large() {
+        // With T = 2k and D = k^2 - 1 only t = k wins; neither float root would be exact here.
+        let k = BigUint::from(10u32).pow(40);
+        let race = Race::new(&k * 2u32, &k * &k - 1u32);
+        assert_eq!(race.winning_count(), BigUint::one());
+
+        let race = Race::new(&k * 2u32, &k * &k);
+        assert_eq!(race.winning_count(), BigUint::zero());
+    }
+}
@@ -0,0 +1,74 @@
+use num::BigUint;
+
+use crate::race::Race;
+
+// The sheet keeps each column's digits as written, so the same parse can be read either as
+// separate races or, ignoring the bad kerning, as one long race.
+pub struct Sheet {
+    times: Vec<String>,
+    distances: Vec<String>,
+}
+
+fn parse_row(line: &str, prefix: &str) -> Vec<String> {
+    line.strip_prefix(prefix)
+        .unwrap_or_else(|| panic!("Expected line to start with {}", prefix))
+        .split_whitespace()
+        .map(|n| {
+            if !n.chars().all(|c| c.is_ascii_digit()) {
+                panic!("Invalid number in input {}", n);
+            }
+            n.to_string()
+        })
+        .collect()
+}
+
+impl Sheet {
+    pub fn from_string(input: &str) -> Self {
+        let mut input = input.lines();
+        let times = parse_row(input.next().unwrap(), "Time:");
+        let distances = parse_row(input.next().unwrap(), "Distance:");
+        if times.len() != distances.len() {
+            panic!("Every time needs a matching distance");
+        }
+        Sheet { times, distances }
+    }
+
+    pub fn races(&self) -> Vec<Race> {
+        self.times
+            .iter()
+            .zip(self.distances.iter())
+            .map(|(time, distance)| {
+                Race::new(
+                    time.parse::<BigUint>().unwrap(),
+                    distance.parse::<BigUint>().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn kerned_race(&self) -> Race {
+        Race::new(
+            self.times.concat().parse::<BigUint>().unwrap(),
+            self.distances.concat().parse::<BigUint>().unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_both_readings() {
+        let sheet = Sheet::from_string(include_str!("../test_files/part_one_and_two.txt"));
+        assert_eq!(
+            sheet.races(),
+            vec![
+                Race::new(7u32, 9u32),
+                Race::new(15u32, 40u32),
+                Race::new(30u32, 200u32)
+            ]
+        );
+        assert_eq!(sheet.kerned_race(), Race::new(71530u32, 940200u32));
+    }
+}
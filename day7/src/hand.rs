@@ -0,0 +1,347 @@
+use itertools::Itertools;
+
+use crate::rules::{Classification, RuleSet};
+
+// A card's strength under the rule set it was read with. Suits never affect strength.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub struct Card {
+    rank: u8,
+    suit: Option<u8>,
+}
+
+impl Card {
+    fn from_chars(rank: char, suit: Option<char>, rules: &RuleSet) -> Self {
+        let rank = match rules.rank(rank) {
+            Some(rank) => rank,
+            None => panic!("Invalid input character {}", rank),
+        };
+        let suit = suit.map(|s| match rules.suit(s) {
+            Some(suit) => suit,
+            None => panic!("Invalid suit {}", s),
+        });
+        Card { rank, suit }
+    }
+}
+
+#[derive(PartialEq, PartialOrd, Ord, Eq, Debug, Clone, Copy)]
+pub enum HandKind {
+    FiveOfAKind = 10,
+    StraightFlush = 9,
+    FourOfAKind = 8,
+    FullHouse = 7,
+    Flush = 6,
+    Straight = 5,
+    ThreeOfAKind = 4,
+    TwoPair = 3,
+    OnePair = 2,
+    HighCard = 1,
+}
+
+impl HandKind {
+    fn from_hand(hand: &[Card], rules: &RuleSet) -> Self {
+        let groups = Self::from_groups(hand, rules);
+        match rules.classification {
+            Classification::Groups => groups,
+            Classification::Poker => {
+                let straight = is_straight(hand, rules);
+                let flush = is_flush(hand, rules);
+                let sequenced = if straight && flush {
+                    HandKind::StraightFlush
+                } else if flush {
+                    HandKind::Flush
+                } else if straight {
+                    HandKind::Straight
+                } else {
+                    HandKind::HighCard
+                };
+                groups.max(sequenced)
+            }
+        }
+    }
+
+    // Wild cards always do best by joining the largest group.
+    fn from_groups(hand: &[Card], rules: &RuleSet) -> Self {
+        let mut ranks = [0u8; MAX_CARDS];
+        let mut natural = 0;
+        for card in hand.iter().filter(|c| !rules.is_wild(c.rank)) {
+            ranks[natural] = card.rank;
+            natural += 1;
+        }
+        let wild = hand.len() - natural;
+
+        let ranks = &mut ranks[..natural];
+        ranks.sort_unstable();
+        let mut counts = [0; 2];
+        for group in ranks.chunk_by(|a, b| a == b) {
+            if group.len() > counts[0] {
+                counts = [group.len(), counts[0]];
+            } else if group.len() > counts[1] {
+                counts[1] = group.len();
+            }
+        }
+        counts[0] += wild;
+
+        if counts[0] >= 5 {
+            HandKind::FiveOfAKind
+        } else if counts[0] == 4 {
+            HandKind::FourOfAKind
+        } else if counts[0] == 3 && counts[1] >= 2 {
+            HandKind::FullHouse
+        } else if counts[0] == 3 {
+            HandKind::ThreeOfAKind
+        } else if counts[1] == 2 {
+            HandKind::TwoPair
+        } else if counts[0] == 2 {
+            HandKind::OnePair
+        } else {
+            HandKind::HighCard
+        }
+    }
+}
+
+// Straights and flushes need every card of a hand of at least five.
+const MIN_SEQUENCE_LEN: usize = 5;
+
+fn is_straight(hand: &[Card], rules: &RuleSet) -> bool {
+    // Wild cards stand in for other cards, so they take no place in the sequence themselves.
+    let position = |rank: u8| (0..rank).filter(|r| !rules.is_wild(*r)).count() as i32;
+    let sequence_len = position(rules.ranks() as u8);
+
+    if hand.len() < MIN_SEQUENCE_LEN || hand.len() as i32 > sequence_len {
+        return false;
+    }
+    let mut positions = [0i32; MAX_CARDS];
+    let mut natural = 0;
+    for card in hand.iter().filter(|c| !rules.is_wild(c.rank)) {
+        positions[natural] = position(card.rank);
+        natural += 1;
+    }
+    let positions = &mut positions[..natural];
+    positions.sort_unstable();
+    if positions.windows(2).any(|w| w[0] == w[1]) {
+        return false;
+    }
+    let fits = |positions: &[i32]| match (positions.first(), positions.last()) {
+        (Some(low), Some(high)) => (high - low) < hand.len() as i32,
+        _ => true,
+    };
+    if fits(positions) {
+        return true;
+    }
+
+    // The strongest card may also play below the weakest, as in an ace-low straight.
+    if positions.last() == Some(&(sequence_len - 1)) {
+        positions.rotate_right(1);
+        positions[0] = -1;
+        return fits(positions);
+    }
+    false
+}
+
+fn is_flush(hand: &[Card], rules: &RuleSet) -> bool {
+    if hand.len() < MIN_SEQUENCE_LEN || !rules.has_suits() {
+        return false;
+    }
+    hand.iter()
+        .filter(|c| !rules.is_wild(c.rank))
+        .map(|c| c.suit)
+        .all_equal()
+}
+
+// Hands live on the stack, so this bounds how many cards can be dealt or played.
+pub const MAX_CARDS: usize = 15;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct Hand {
+    pub bid: u32,
+    kind: HandKind,
+    cards: [Card; MAX_CARDS],
+    len: usize,
+    // Computed once, as sorting compares it far more often than hands are built.
+    key: u128,
+}
+
+impl Hand {
+    pub fn from_string(input: &str, rules: &RuleSet) -> Self {
+        let mut input = input.split_whitespace();
+        let mut chars = input.next().unwrap().chars();
+        let mut dealt = [Card::default(); MAX_CARDS];
+        let mut len = 0;
+        while let Some(rank) = chars.next() {
+            let suit = if rules.has_suits() {
+                Some(chars.next().expect("Every card needs a suit"))
+            } else {
+                None
+            };
+            if len == MAX_CARDS {
+                panic!("Hands can have at most {} cards", MAX_CARDS);
+            }
+            dealt[len] = Card::from_chars(rank, suit, rules);
+            len += 1;
+        }
+
+        let bid = input.next().unwrap().parse().unwrap();
+
+        match rules.hand_size {
+            Some(size) if size < len => (0u32..1 << len)
+                .filter(|mask| mask.count_ones() as usize == size)
+                .map(|mask| {
+                    let mut cards = [Card::default(); MAX_CARDS];
+                    let chosen = (0..len).filter(|i| mask & (1 << i) != 0);
+                    for (card, i) in cards.iter_mut().zip(chosen) {
+                        *card = dealt[i];
+                    }
+                    Hand::new(&cards[..size], bid, rules)
+                })
+                .max()
+                .unwrap(),
+            _ => Hand::new(&dealt[..len], bid, rules),
+        }
+    }
+
+    fn new(hand: &[Card], bid: u32, rules: &RuleSet) -> Self {
+        let kind = HandKind::from_hand(hand, rules);
+        let mut cards = [Card::default(); MAX_CARDS];
+        cards[..hand.len()].copy_from_slice(hand);
+        let mut hand = Hand {
+            kind,
+            cards,
+            len: hand.len(),
+            bid,
+            key: 0,
+        };
+        hand.key = hand.compute_key();
+        hand
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards[..self.len]
+    }
+
+    // The kind sits above one byte per card, in the order the cards were dealt. Ranks are
+    // shifted up by one so that a missing card sorts below every real one.
+    fn compute_key(&self) -> u128 {
+        let mut key = (self.kind as u128) << (8 * MAX_CARDS);
+        for (i, card) in self.cards().iter().enumerate() {
+            key |= (card.rank as u128 + 1) << (8 * (MAX_CARDS - 1 - i));
+        }
+        key
+    }
+
+    pub fn key(&self) -> u128 {
+        self.key
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn kind(hand: &str, rules: &RuleSet) -> HandKind {
+        Hand::from_string(&format!("{} 0", hand), rules).kind
+    }
+
+    #[test]
+    fn test_wild_cards_join_largest_group() {
+        let standard = RuleSet::standard();
+        let jokers = RuleSet::jokers();
+        assert_eq!(kind("KTJJT", &standard), HandKind::TwoPair);
+        assert_eq!(kind("KTJJT", &jokers), HandKind::FourOfAKind);
+        assert_eq!(kind("JJJJJ", &jokers), HandKind::FiveOfAKind);
+    }
+
+    #[test]
+    fn test_poker_kinds() {
+        let poker =
+            RuleSet::from_string("order = 23456789TJQKA\nsuits = cdhs\nclassification = poker")
+                .unwrap();
+        assert_eq!(kind("9h8hThJhQh", &poker), HandKind::StraightFlush);
+        assert_eq!(kind("Ad2c3h4s5s", &poker), HandKind::Straight);
+        assert_eq!(kind("Kd2c3h4s5s", &poker), HandKind::HighCard);
+        assert_eq!(kind("Qd2dKd4d9d", &poker), HandKind::Flush);
+        assert_eq!(kind("QdQhQc4d4s", &poker), HandKind::FullHouse);
+        assert_eq!(kind("QdQhQc4d", &poker), HandKind::ThreeOfAKind);
+    }
+
+    #[test]
+    fn test_poker_wild_cards() {
+        let poker = RuleSet::from_string(
+            "order = J23456789TQKA\nwild = J\nsuits = cdhs\nclassification = poker",
+        )
+        .unwrap();
+        assert_eq!(kind("AhJc3h4h5h", &poker), HandKind::StraightFlush);
+        assert_eq!(kind("KdJcJh4s5s", &poker), HandKind::ThreeOfAKind);
+        assert_eq!(kind("9dJcJhQsKs", &poker), HandKind::Straight);
+    }
+
+    #[test]
+    fn test_best_five_of_seven() {
+        let poker = RuleSet::from_string(include_str!("../rules/poker.txt")).unwrap();
+        let hand = Hand::from_string("2c7dAhKhQhJhTh 5", &poker);
+        assert_eq!(hand.kind, HandKind::StraightFlush);
+        assert_eq!(hand.cards().len(), 5);
+
+        let hand = Hand::from_string("2c2d9s9h3c3dKs 5", &poker);
+        assert_eq!(hand.kind, HandKind::TwoPair);
+        // Ties are still broken card by card, so the two pair led by the nines is played.
+        assert_eq!(hand.cards()[0].rank, poker.rank('9').unwrap());
+    }
+
+    // The ordering `Hand` had before it was packed into a key.
+    fn card_by_card(a: &Hand, b: &Hand) -> std::cmp::Ordering {
+        match a.kind.cmp(&b.kind) {
+            core::cmp::Ordering::Equal => {}
+            ord => return ord,
+        }
+        for (i, card) in a.cards().iter().enumerate() {
+            match card.rank.cmp(&b.cards()[i].rank) {
+                core::cmp::Ordering::Equal => {}
+                ord => return ord,
+            }
+        }
+        core::cmp::Ordering::Equal
+    }
+
+    #[test]
+    fn test_key_matches_card_by_card_ordering() {
+        let labels: Vec<char> = "23456789TJQKA".chars().collect();
+        let mut seed: u64 = 7;
+        let mut lines = include_str!("../test_files/part_one_and_two.txt")
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        for _ in 0..300 {
+            let hand: String = (0..5)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    // Few labels make groups, and so ties on kind, more likely.
+                    labels[(seed >> 33) as usize % 5 + 8 * ((seed >> 40) as usize % 2)]
+                })
+                .collect();
+            lines.push(format!("{} 1", hand));
+        }
+
+        for rules in [RuleSet::standard(), RuleSet::jokers()] {
+            let hands: Vec<Hand> = lines.iter().map(|l| Hand::from_string(l, &rules)).collect();
+            for a in &hands {
+                for b in &hands {
+                    assert_eq!(a.cmp(b), card_by_card(a, b), "{:?} {:?}", a, b);
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,86 @@
-mod part_one;
-mod part_two;
+mod hand;
+mod rules;
+
+use hand::Hand;
+use rules::RuleSet;
+
+fn total_winnings(input: &str, rules: &RuleSet) -> u32 {
+    let mut hands: Vec<Hand> = input
+        .lines()
+        .map(|line| Hand::from_string(line, rules))
+        .collect();
+    hands.sort_unstable_by_key(Hand::key);
+    hands
+        .iter()
+        .enumerate()
+        .map(|(i, hand)| (i + 1) as u32 * hand.bid)
+        .sum()
+}
+
+fn part_one(input: &str) -> u32 {
+    total_winnings(input, &RuleSet::standard())
+}
+
+fn part_two(input: &str) -> u32 {
+    total_winnings(input, &RuleSet::jokers())
+}
 
 fn main() {
     let input = include_str!("../input.txt");
 
-    println!("Part one: {}", part_one::part_one(input));
-    println!("Part two: {}", part_two::part_two(input));
+    println!("Part one: {}", part_one(input));
+    println!("Part two: {}", part_two(input));
+
+    // Any extra arguments are rule files to score the same hands with, or with the hands in
+    // the file after `--hands`.
+    let mut hands = input.to_string();
+    let mut paths = vec![];
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg != "--hands" {
+            paths.push(arg);
+            continue;
+        }
+        let Some(hands_path) = args.next() else {
+            eprintln!("Expected a hands file after --hands");
+            return;
+        };
+        match std::fs::read_to_string(&hands_path) {
+            Ok(input) => hands = input,
+            Err(e) => {
+                eprintln!("Could not read hands file {}: {}", hands_path, e);
+                return;
+            }
+        }
+    }
+    for path in paths {
+        match RuleSet::from_file(&path) {
+            Ok(rules) => println!("{}: {}", path, total_winnings(&hands, &rules)),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let input = include_str!("../test_files/part_one_and_two.txt");
+        assert_eq!(part_one(input), 6440)
+    }
+
+    #[test]
+    fn test_part_two() {
+        let input = include_str!("../test_files/part_one_and_two.txt");
+        assert_eq!(part_two(input), 5905)
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let input = include_str!("../test_files/part_one_and_two.txt");
+        let rules = RuleSet::from_string("order = J23456789TQKA\nwild = J").unwrap();
+        assert_eq!(total_winnings(input, &rules), 5905)
+    }
 }
@@ -0,0 +1,162 @@
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Classification {
+    Groups,
+    Poker,
+}
+
+impl Classification {
+    fn from_str(input: &str) -> Result<Self, String> {
+        match input {
+            "groups" => Ok(Self::Groups),
+            "poker" => Ok(Self::Poker),
+            _ => Err(format!("Unknown classification {}", input)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleSet {
+    // Card labels from weakest to strongest.
+    order: Vec<char>,
+    wild: Vec<char>,
+    // When set, every card is written as its label followed by one of these suits.
+    suits: Vec<char>,
+    // When set, only the best `hand_size` cards of each dealt hand are played.
+    pub hand_size: Option<usize>,
+    pub classification: Classification,
+}
+
+impl RuleSet {
+    pub fn standard() -> Self {
+        Self::from_string(include_str!("../rules/standard.txt")).unwrap()
+    }
+
+    pub fn jokers() -> Self {
+        Self::from_string(include_str!("../rules/jokers.txt")).unwrap()
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read rule file {}: {}", path, e))?;
+        Self::from_string(&input)
+    }
+
+    pub fn from_string(input: &str) -> Result<Self, String> {
+        let mut order = None;
+        let mut wild = Vec::new();
+        let mut suits = Vec::new();
+        let mut hand_size = None;
+        let mut classification = Classification::Groups;
+
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Expected `key = value`, got {}", line))?;
+            let value = value.trim();
+            match key.trim() {
+                "order" => {
+                    order = Some(
+                        value
+                            .chars()
+                            .filter(|c| !c.is_whitespace())
+                            .collect::<Vec<_>>(),
+                    )
+                }
+                "wild" => wild = value.chars().filter(|c| !c.is_whitespace()).collect(),
+                "suits" => suits = value.chars().filter(|c| !c.is_whitespace()).collect(),
+                "hand_size" => {
+                    hand_size = match value.parse() {
+                        Ok(0) | Err(_) => return Err(format!("Invalid hand size {}", value)),
+                        Ok(size) => Some(size),
+                    }
+                }
+                "classification" => classification = Classification::from_str(value)?,
+                key => return Err(format!("Unknown rule {}", key)),
+            }
+        }
+
+        let order = order.ok_or("Rule file has no card order")?;
+        if order.is_empty() {
+            return Err("Card order can not be empty".to_string());
+        }
+        if order.len() > u8::MAX as usize {
+            return Err(format!("At most {} cards can be ranked", u8::MAX));
+        }
+        for (i, c) in order.iter().enumerate() {
+            if order[i + 1..].contains(c) {
+                return Err(format!("Card {} appears twice in the order", c));
+            }
+        }
+        if let Some(c) = wild.iter().find(|c| !order.contains(c)) {
+            return Err(format!("Wild card {} is not in the order", c));
+        }
+
+        for (i, c) in suits.iter().enumerate() {
+            if suits[i + 1..].contains(c) {
+                return Err(format!("Suit {} appears twice", c));
+            }
+        }
+
+        Ok(RuleSet {
+            order,
+            wild,
+            suits,
+            hand_size,
+            classification,
+        })
+    }
+
+    pub fn rank(&self, c: char) -> Option<u8> {
+        self.order.iter().position(|o| *o == c).map(|i| i as u8)
+    }
+
+    pub fn is_wild(&self, rank: u8) -> bool {
+        self.wild.contains(&self.order[rank as usize])
+    }
+
+    pub fn ranks(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn has_suits(&self) -> bool {
+        !self.suits.is_empty()
+    }
+
+    pub fn suit(&self, c: char) -> Option<u8> {
+        self.suits.iter().position(|s| *s == c).map(|i| i as u8)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        let standard = RuleSet::standard();
+        assert!(standard.rank('J') > standard.rank('T'));
+        assert!(!standard.is_wild(standard.rank('J').unwrap()));
+
+        let jokers = RuleSet::jokers();
+        assert_eq!(jokers.rank('J'), Some(0));
+        assert!(jokers.is_wild(0));
+
+        let spaced = RuleSet::from_string("order = 2 3 4 5\nwild = 3").unwrap();
+        assert_eq!(spaced.ranks(), 4);
+        assert_eq!(spaced.rank('5'), Some(3));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(RuleSet::from_string("wild = J").is_err());
+        assert!(RuleSet::from_string("order = 23A\nwild = K").is_err());
+        assert!(RuleSet::from_string("order = 2323").is_err());
+        assert!(RuleSet::from_string("order = 23\nclassification = bridge").is_err());
+        assert!(RuleSet::from_string("order = 23\nhand_size = 0").is_err());
+        assert!(RuleSet::from_string("order = 23\nsuits = hh").is_err());
+    }
+}
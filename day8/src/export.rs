@@ -0,0 +1,232 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use serde_json::json;
+
+use crate::ghost::GhostCycle;
+use crate::network::{Direction, Network};
+
+const CYCLE_COLOURS: [&str; 6] = ["red", "blue", "darkgreen", "orange", "purple", "brown"];
+
+pub struct Export<'a> {
+    network: &'a Network,
+    is_start: Vec<bool>,
+    is_target: Vec<bool>,
+    ghosts: Vec<GhostCycle>,
+    // Instruction indices at which each (node, direction) edge is taken by some ghost.
+    taken_at: BTreeMap<(usize, Direction), BTreeSet<usize>>,
+}
+
+impl<'a> Export<'a> {
+    pub fn new(
+        network: &'a Network,
+        is_start: impl Fn(&str) -> bool,
+        is_target: impl Fn(&str) -> bool,
+    ) -> Self {
+        let is_start = network.mask(is_start);
+        let is_target = network.mask(is_target);
+        let ghosts: Vec<_> = (0..network.len())
+            .filter(|id| is_start[*id])
+            .filter_map(|start| GhostCycle::detect(network, start, &is_target, None))
+            .collect();
+
+        let mut taken_at: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+        for ghost in &ghosts {
+            for (step, node) in ghost.path.iter().enumerate() {
+                let instruction = step % network.directions.len();
+                taken_at
+                    .entry((*node, network.directions[instruction]))
+                    .or_default()
+                    .insert(instruction);
+            }
+        }
+
+        Export {
+            network,
+            is_start,
+            is_target,
+            ghosts,
+            taken_at,
+        }
+    }
+
+    fn cycle_edges(&self, ghost: &GhostCycle) -> BTreeSet<(usize, Direction)> {
+        let directions = &self.network.directions;
+        ghost
+            .cycle()
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let step = ghost.tail as usize + i;
+                (*node, directions[step % directions.len()])
+            })
+            .collect()
+    }
+
+    fn instructions(&self, node: usize, direction: Direction) -> Vec<usize> {
+        self.taken_at
+            .get(&(node, direction))
+            .map(|taken| taken.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn to_dot(&self, annotate: bool) -> String {
+        let network = self.network;
+        let quote = |id: usize| {
+            format!(
+                "\"{}\"",
+                network.name(id).replace('\\', "\\\\").replace('"', "\\\"")
+            )
+        };
+        let cycles: Vec<_> = self.ghosts.iter().map(|g| self.cycle_edges(g)).collect();
+
+        let mut out = String::from("digraph network {\n");
+        for id in 0..network.len() {
+            let mut attributes = vec![];
+            if self.is_start[id] {
+                attributes.push("style=filled, fillcolor=palegreen".to_string());
+            }
+            if self.is_target[id] {
+                attributes.push("shape=doublecircle".to_string());
+            }
+            if attributes.is_empty() {
+                writeln!(out, "    {};", quote(id)).unwrap();
+            } else {
+                writeln!(out, "    {} [{}];", quote(id), attributes.join(", ")).unwrap();
+            }
+        }
+
+        for id in 0..network.len() {
+            for direction in [Direction::Left, Direction::Right] {
+                let mut label = direction.to_char().to_string();
+                if annotate {
+                    let taken = self.instructions(id, direction);
+                    if !taken.is_empty() {
+                        label = format!(
+                            "{} {}",
+                            label,
+                            taken
+                                .iter()
+                                .map(|i| i.to_string())
+                                .collect::<Vec<_>>()
+                                .join(",")
+                        );
+                    }
+                }
+                let mut attributes = vec![format!("label=\"{}\"", label)];
+                let colours: Vec<_> = cycles
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, edges)| edges.contains(&(id, direction)))
+                    .map(|(i, _)| CYCLE_COLOURS[i % CYCLE_COLOURS.len()])
+                    .collect();
+                if !colours.is_empty() {
+                    attributes.push(format!("color=\"{}\", penwidth=2", colours.join(":")));
+                }
+                writeln!(
+                    out,
+                    "    {} -> {} [{}];",
+                    quote(id),
+                    quote(network.next(id, direction)),
+                    attributes.join(", ")
+                )
+                .unwrap();
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_json(&self, annotate: bool) -> String {
+        let network = self.network;
+        let nodes: Vec<_> = (0..network.len())
+            .map(|id| {
+                json!({
+                    "name": network.name(id),
+                    "left": network.name(network.next(id, Direction::Left)),
+                    "right": network.name(network.next(id, Direction::Right)),
+                    "start": self.is_start[id],
+                    "target": self.is_target[id],
+                })
+            })
+            .collect();
+
+        let ghosts: Vec<_> = self
+            .ghosts
+            .iter()
+            .map(|ghost| {
+                json!({
+                    "start": network.name(ghost.start),
+                    "tail": ghost.tail,
+                    "length": ghost.length,
+                    "cycle": ghost.cycle().iter().map(|id| network.name(*id)).collect::<Vec<_>>(),
+                    "target_steps": ghost.tail_hits.iter().chain(ghost.cycle_hits.iter()).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+
+        let mut out = json!({
+            "directions": network.directions.iter().map(|d| d.to_char()).collect::<String>(),
+            "nodes": nodes,
+            "ghosts": ghosts,
+        });
+        if annotate {
+            out["edges"] = self
+                .taken_at
+                .iter()
+                .map(|((id, direction), taken)| {
+                    json!({
+                        "from": network.name(*id),
+                        "to": network.name(network.next(*id, *direction)),
+                        "direction": direction.to_char().to_string(),
+                        "instructions": taken,
+                    })
+                })
+                .collect();
+        }
+        serde_json::to_string_pretty(&out).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn export(network: &Network) -> Export<'_> {
+        Export::new(network, |n| n.ends_with('A'), |n| n.ends_with('Z'))
+    }
+
+    #[test]
+    fn test_dot() {
+        let network = Network::from_str(include_str!("../test_files/part_two.txt"));
+        let dot = export(&network).to_dot(true);
+        assert!(dot.starts_with("digraph network {\n"));
+        assert!(dot.contains("    \"11A\" [style=filled, fillcolor=palegreen];\n"));
+        assert!(dot.contains("    \"22Z\" [shape=doublecircle];\n"));
+        // 11B -> 11Z -> 11B is the first ghost's cycle, always taken going right then left.
+        assert!(
+            dot.contains("    \"11B\" -> \"11Z\" [label=\"R 1\", color=\"red\", penwidth=2];\n")
+        );
+        assert!(
+            dot.contains("    \"11Z\" -> \"11B\" [label=\"L 0\", color=\"red\", penwidth=2];\n")
+        );
+        assert!(dot.contains("    \"XXX\" -> \"XXX\" [label=\"L\"];\n"));
+    }
+
+    #[test]
+    fn test_json() {
+        let network = Network::from_str(include_str!("../test_files/part_two.txt"));
+        let json: serde_json::Value =
+            serde_json::from_str(&export(&network).to_json(true)).unwrap();
+        assert_eq!(json["directions"], "LR");
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 8);
+        assert_eq!(json["ghosts"][1]["start"], "22A");
+        assert_eq!(json["ghosts"][1]["tail"], 1);
+        assert_eq!(json["ghosts"][1]["cycle"].as_array().unwrap().len(), 6);
+        assert_eq!(json["edges"][0]["instructions"], json!([0]));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&export(&network).to_json(false)).unwrap();
+        assert!(json.get("edges").is_none());
+    }
+}
@@ -0,0 +1,362 @@
+use num::integer::{ExtendedGcd, Integer};
+use num::{BigInt, One, Zero};
+
+use crate::network::{Network, WalkError};
+
+// A ghost's walk is fully described by its (node, instruction index) state, so it must
+// eventually repeat: a tail of `tail` steps followed by a cycle of `length` steps.
+pub struct GhostCycle {
+    pub tail: u64,
+    pub length: u64,
+    pub start: usize,
+    // Nodes visited at each step, up to the last step before the cycle repeats.
+    pub path: Vec<usize>,
+    // Steps at which the ghost is on a target, before and within the first cycle.
+    pub tail_hits: Vec<u64>,
+    pub cycle_hits: Vec<u64>,
+}
+
+impl GhostCycle {
+    // Gives up once the walk passes `step_limit` steps without repeating.
+    pub fn detect(
+        network: &Network,
+        start: usize,
+        is_target: &[bool],
+        step_limit: Option<u64>,
+    ) -> Option<Self> {
+        let directions = network.directions.len();
+        let mut first_seen = vec![None; network.len() * directions];
+        let mut path = Vec::new();
+
+        let mut current = start;
+        let mut step = 0;
+        let tail = loop {
+            let state = current * directions + step % directions;
+            if let Some(seen) = first_seen[state] {
+                break seen;
+            }
+            if step_limit.is_some_and(|limit| step as u64 > limit) {
+                return None;
+            }
+            first_seen[state] = Some(step as u64);
+            path.push(current);
+            current = network.next(current, network.directions[step % directions]);
+            step += 1;
+        };
+
+        let (tail_hits, cycle_hits) = path
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| is_target[**node])
+            .map(|(step, _)| step as u64)
+            .partition(|step| *step < tail);
+
+        Some(GhostCycle {
+            tail,
+            length: path.len() as u64 - tail,
+            start,
+            path,
+            tail_hits,
+            cycle_hits,
+        })
+    }
+
+    pub fn cycle(&self) -> &[usize] {
+        &self.path[self.tail as usize..]
+    }
+
+    pub fn is_hit(&self, step: u128) -> bool {
+        let tail = self.tail as u128;
+        if step < tail {
+            self.tail_hits.binary_search(&(step as u64)).is_ok()
+        } else {
+            let in_cycle = tail + (step - tail) % self.length as u128;
+            self.cycle_hits.binary_search(&(in_cycle as u64)).is_ok()
+        }
+    }
+}
+
+// Generalised CRT: merges x = a1 (mod m1) and x = a2 (mod m2), whose moduli need not be coprime.
+// The merged modulus grows with every ghost even when the answer is small, so this works on
+// big integers.
+fn combine((a1, m1): &(BigInt, BigInt), (a2, m2): (BigInt, BigInt)) -> Option<(BigInt, BigInt)> {
+    let ExtendedGcd { gcd: g, x: p, .. } = m1.extended_gcd(&m2);
+    let difference = a2 - a1;
+    if !(&difference % &g).is_zero() {
+        return None;
+    }
+    let reduced = &m2 / &g;
+    let k = (difference / &g).mod_floor(&reduced) * p.mod_floor(&reduced) % &reduced;
+    let lcm = m1 / &g * m2;
+    Some(((a1 + m1 * k).mod_floor(&lcm), lcm))
+}
+
+// Past this many congruences, the ghosts not merged yet are checked step by step instead.
+const MAX_CONGRUENCES: usize = 1 << 12;
+
+pub fn first_simultaneous_arrival(
+    ghosts: &[GhostCycle],
+    step_budget: Option<u64>,
+) -> Result<u128, WalkError> {
+    let longest_tail = ghosts
+        .iter()
+        .map(|g| g.tail)
+        .max()
+        .ok_or(WalkError::NoStart)?;
+
+    // Before every ghost is in its cycle, arrivals can only be checked one by one.
+    if let Some(step) =
+        (0..longest_tail).find(|step| ghosts.iter().all(|g| g.is_hit(*step as u128)))
+    {
+        return Ok(step as u128);
+    }
+
+    // After that each ghost arrives exactly on the steps matching one of its cycle offsets.
+    // Merging a ghost turns every residue into one per offset, all modulo the lcm of the
+    // cycles merged so far, so the residues can multiply with each ghost. Once they would
+    // pass the limit, the remaining ghosts are sieved instead.
+    let mut residues = vec![BigInt::zero()];
+    let mut modulus = BigInt::one();
+    let mut merged = 0;
+    for ghost in ghosts {
+        if residues.len() * ghost.cycle_hits.len() > MAX_CONGRUENCES {
+            break;
+        }
+        let length = BigInt::from(ghost.length);
+        let congruence = |residue: &BigInt, hit: u64| {
+            combine(
+                &(residue.clone(), modulus.clone()),
+                (BigInt::from(hit), length.clone()),
+            )
+        };
+        residues = residues
+            .iter()
+            .flat_map(|residue| {
+                ghost
+                    .cycle_hits
+                    .iter()
+                    .filter_map(|hit| congruence(residue, *hit).map(|(a, _)| a))
+            })
+            .collect();
+        residues.sort();
+        residues.dedup();
+        modulus = modulus.lcm(&length);
+        merged += 1;
+        if residues.is_empty() {
+            return Err(WalkError::NoSimultaneousArrival);
+        }
+    }
+
+    // Lifts every residue to its first step at or after the longest tail, in order.
+    let longest_tail = BigInt::from(longest_tail);
+    let mut candidates: Vec<BigInt> = residues
+        .into_iter()
+        .map(|a| {
+            if a >= longest_tail {
+                a
+            } else {
+                let periods = (&longest_tail - &a + &modulus - 1u8) / &modulus;
+                a + periods * &modulus
+            }
+        })
+        .collect();
+    candidates.sort();
+
+    // Adding whole periods keeps the candidates in order, and after lcm(all cycles) steps
+    // nothing new can line up. Without a budget that can still be a long search when no
+    // arrival exists.
+    let rest = &ghosts[merged..];
+    let full_period = rest.iter().fold(modulus.clone(), |period, g| {
+        period.lcm(&BigInt::from(g.length))
+    });
+    let mut offset = BigInt::zero();
+    while offset < full_period {
+        for candidate in &candidates {
+            let step = u128::try_from(candidate + &offset).map_err(|_| WalkError::Overflow)?;
+            if let Some(budget) = step_budget.filter(|budget| step > *budget as u128) {
+                return Err(WalkError::StepBudgetExceeded(budget));
+            }
+            if rest.iter().all(|g| g.is_hit(step)) {
+                return Ok(step);
+            }
+        }
+        offset += &modulus;
+    }
+    Err(WalkError::NoSimultaneousArrival)
+}
+
+// Walks every ghost together for at most `budget` steps.
+fn lockstep_arrival(
+    network: &Network,
+    starts: &[usize],
+    is_target: &[bool],
+    budget: u64,
+) -> Option<u64> {
+    let mut current = starts.to_vec();
+    let directions = network.directions.iter().cycle();
+    for (step, direction) in (0..=budget).zip(directions) {
+        if current.iter().all(|node| is_target[*node]) {
+            return Some(step);
+        }
+        current
+            .iter_mut()
+            .for_each(|node| *node = network.next(*node, *direction));
+    }
+    None
+}
+
+pub fn ghost_walk(
+    network: &Network,
+    is_start: impl Fn(&str) -> bool,
+    is_target: impl Fn(&str) -> bool,
+    step_budget: Option<u64>,
+) -> Result<u128, WalkError> {
+    let is_target = network.mask(is_target);
+    let starts = network.nodes_where(is_start);
+    if starts.is_empty() {
+        return Err(WalkError::NoStart);
+    }
+    network.check_reachable(&starts, &is_target, step_budget)?;
+
+    // A ghost that hasn't repeated within the budget can't be solved by its cycle, but any
+    // arrival within the budget is then found by walking them all at once.
+    let ghosts: Option<Vec<_>> = starts
+        .iter()
+        .map(|start| GhostCycle::detect(network, *start, &is_target, step_budget))
+        .collect();
+    let arrival = match (ghosts, step_budget) {
+        (Some(ghosts), _) => first_simultaneous_arrival(&ghosts, step_budget)?,
+        (None, Some(budget)) => lockstep_arrival(network, &starts, &is_target, budget)
+            .ok_or(WalkError::StepBudgetExceeded(budget))? as u128,
+        (None, None) => unreachable!(),
+    };
+    match step_budget {
+        Some(budget) if arrival > budget as u128 => Err(WalkError::StepBudgetExceeded(budget)),
+        _ => Ok(arrival),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn solve(input: &str) -> Result<u128, WalkError> {
+        let network = Network::from_str(input);
+        ghost_walk(&network, |n| n.ends_with('A'), |n| n.ends_with('Z'), None)
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let network = Network::from_str(include_str!("../test_files/part_two.txt"));
+        let is_target = network.mask(|name| name.ends_with('Z'));
+        let start = network.nodes_where(|name| name == "22A")[0];
+        let ghost = GhostCycle::detect(&network, start, &is_target, None).unwrap();
+        assert_eq!((ghost.tail, ghost.length), (1, 6));
+        assert_eq!(ghost.cycle_hits, vec![3, 6]);
+        assert!(ghost.is_hit(9) && !ghost.is_hit(10));
+    }
+
+    #[test]
+    fn test_offset_cycles() {
+        // The first arrivals are at 2 and 1, but the cycles only line up at step 5.
+        let input = "L\n\n1A = (11, 11)\n11 = (1Z, 1Z)\n1Z = (12, 12)\n12 = (11, 11)\n2A = (2Z, 2Z)\n2Z = (2A, 2A)";
+        assert_eq!(solve(input), Ok(5));
+    }
+
+    #[test]
+    fn test_combine_unreduced_residue() {
+        let m1: BigInt = BigInt::from(10).pow(30u32) + 57;
+        let a2: BigInt = BigInt::from(12) * BigInt::from(10).pow(37u32);
+        let seven = BigInt::from(7);
+        let (x, m) = combine(&(BigInt::one(), m1.clone()), (a2.clone(), seven.clone())).unwrap();
+        assert_eq!(m, &m1 * &seven);
+        assert_eq!((&x % &m1, &x % &seven), (BigInt::one(), a2 % &seven));
+    }
+
+    #[test]
+    fn test_coprime_cycles() {
+        // Thirty ghosts with prime cycles up to 113 all arrive at step 1, although the product
+        // of their cycle lengths is far past u128.
+        let primes = (2u32..=113).filter(|n| (2..*n).all(|d| n % d != 0));
+        let mut input = String::from("L\n\n");
+        for (i, prime) in primes.enumerate() {
+            let mut cycle = vec![format!("{}A", i), format!("{}Z", i)];
+            cycle.extend((2..prime).map(|j| format!("{}X{}", i, j)));
+            for (j, node) in cycle.iter().enumerate() {
+                let next = &cycle[(j + 1) % cycle.len()];
+                input += &format!("{} = ({}, {})\n", node, next, next);
+            }
+        }
+        assert_eq!(solve(&input), Ok(1));
+    }
+
+    #[test]
+    fn test_many_hits_per_cycle() {
+        // Merging every ghost would leave 2^24 congruences, but they all arrive at step 1.
+        let primes = (5u32..).filter(|n| (2..*n).all(|d| n % d != 0)).take(24);
+        let mut input = String::from("L\n\n");
+        for (i, prime) in primes.enumerate() {
+            let mut cycle = vec![format!("{}A", i), format!("{}Z", i)];
+            cycle.extend((2..prime).map(|j| {
+                if j == 3 {
+                    format!("{}X{}Z", i, j)
+                } else {
+                    format!("{}X{}", i, j)
+                }
+            }));
+            for (j, node) in cycle.iter().enumerate() {
+                let next = &cycle[(j + 1) % cycle.len()];
+                input += &format!("{} = ({}, {})\n", node, next, next);
+            }
+        }
+        assert_eq!(solve(&input), Ok(1));
+    }
+
+    #[test]
+    fn test_tail_arrival() {
+        let input =
+            "L\n\n1A = (1Z, 1Z)\n1Z = (11, 11)\n11 = (11, 11)\n2A = (2Z, 2Z)\n2Z = (2A, 2A)";
+        assert_eq!(solve(input), Ok(1));
+    }
+
+    #[test]
+    fn test_no_simultaneous_arrival() {
+        let input =
+            "L\n\n1A = (11, 11)\n11 = (1Z, 1Z)\n1Z = (11, 11)\n2A = (2Z, 2Z)\n2Z = (2A, 2A)";
+        assert_eq!(solve(input), Err(WalkError::NoSimultaneousArrival));
+    }
+
+    #[test]
+    fn test_unreachable_ghost() {
+        let input = "L\n\n1A = (11, 11)\n11 = (11, 11)\n2A = (2Z, 2Z)\n2Z = (2A, 2A)";
+        assert_eq!(
+            solve(input),
+            Err(WalkError::Unreachable(vec!["1A".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_ghost_step_budget() {
+        let network = Network::from_str(include_str!("../test_files/part_two.txt"));
+        let walk =
+            |budget| ghost_walk(&network, |n| n.ends_with('A'), |n| n.ends_with('Z'), budget);
+        assert_eq!(walk(Some(6)), Ok(6));
+        assert_eq!(walk(Some(5)), Err(WalkError::StepBudgetExceeded(5)));
+    }
+
+    #[test]
+    fn test_budget_stops_cycle_detection() {
+        // The first ghost arrives at step 1 but takes 5 steps to repeat.
+        let input = "L\n\n1A = (1Z, 1Z)\n1Z = (11, 11)\n11 = (12, 12)\n12 = (13, 13)\n13 = (1A, 1A)\n2A = (2Z, 2Z)\n2Z = (2A, 2A)";
+        let network = Network::from_str(input);
+        let is_target = network.mask(|n| n.ends_with('Z'));
+        let start = network.nodes_where(|n| n == "1A")[0];
+        assert!(GhostCycle::detect(&network, start, &is_target, Some(2)).is_none());
+
+        let walk =
+            |budget| ghost_walk(&network, |n| n.ends_with('A'), |n| n.ends_with('Z'), budget);
+        assert_eq!(walk(Some(2)), Ok(1));
+        assert_eq!(walk(None), Ok(1));
+        assert_eq!(walk(Some(0)), Err(WalkError::StepBudgetExceeded(0)));
+    }
+}
@@ -1,129 +1,58 @@
-use std::collections::HashMap;
-
-struct Node {
-    left: String,
-    right: String,
+mod export;
+mod ghost;
+mod network;
+
+use export::Export;
+use network::{Network, WalkError};
+
+fn part_one(input: &str, step_budget: Option<u64>) -> Result<u32, WalkError> {
+    let network = Network::from_str(input);
+    let arrivals =
+        network.first_arrivals(|name| name == "AAA", |name| name == "ZZZ", step_budget)?;
+    let steps = arrivals.first().ok_or(WalkError::NoStart)?;
+    u32::try_from(*steps).map_err(|_| WalkError::Overflow)
 }
 
-fn part_one(input: &str) -> u32 {
-    let mut input = input.lines();
-    let mut directions = input.next().unwrap().chars().cycle();
-    input.next();
-
-    let mut graph = HashMap::new();
-    input.for_each(|line| {
-        let source = line[0..3].to_string();
-        let left = line[7..10].to_string();
-        let right = line[12..15].to_string();
-        graph.insert(source, Node { left, right });
-    });
+fn part_two(input: &str, step_budget: Option<u64>) -> Result<u128, WalkError> {
+    let network = Network::from_str(input);
+    ghost::ghost_walk(
+        &network,
+        |name| name.ends_with('A'),
+        |name| name.ends_with('Z'),
+        step_budget,
+    )
+}
 
-    let mut steps = 0;
-    let mut current_id = "AAA".to_string();
-    loop {
-        if current_id == "ZZZ" {
-            break;
+fn main() {
+    let input = include_str!("../input.txt");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // `dot` or `json` prints the network instead, with `--annotate` adding instruction indices.
+    if let Some(format) = args.first().filter(|a| *a == "dot" || *a == "json") {
+        let network = Network::from_str(input);
+        let export = Export::new(&network, |n| n.ends_with('A'), |n| n.ends_with('Z'));
+        let annotate = args.iter().any(|a| a == "--annotate");
+        if format == "dot" {
+            print!("{}", export.to_dot(annotate));
+        } else {
+            println!("{}", export.to_json(annotate));
         }
-        let current_node = graph.get(&current_id).unwrap();
-        current_id = match directions.next().unwrap() {
-            'L' => current_node.left.clone(),
-            'R' => current_node.right.clone(),
-            _ => panic!("Invalid direction in input"),
-        };
-        steps += 1;
+        return;
     }
-    steps
-}
-
-#[derive(Clone)]
-struct FastNode {
-    left: u16,
-    right: u16,
-}
 
-enum Direction {
-    Left,
-    Right,
-}
+    // Otherwise an optional argument caps how many steps a walk may take.
+    let step_budget = args
+        .first()
+        .map(|budget| budget.parse().expect("The step budget must be a number"));
 
-fn id_to_u16(input: &str) -> u16 {
-    let mut out = 0;
-    for i in 0..3 {
-        out |= ((input.as_bytes()[i as usize] & 0b00011111) as u16) << (i * 5);
+    match part_one(input, step_budget) {
+        Ok(steps) => println!("Part one: {}", steps),
+        Err(e) => println!("Part one: {}", e),
     }
-    out
-}
-
-fn part_two(input: &str) -> u128 {
-    let mut input = input.lines();
-    let directions: Vec<_> = input
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| match c {
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => panic!("Invalid direction in input"),
-        })
-        .collect();
-    input.next();
-
-    let mut graph = vec![FastNode { left: 0, right: 0 }; u16::MAX as usize];
-
-    let mut current_nodes = Vec::new();
-    input.for_each(|line| {
-        let source_str = &line[0..3];
-        let source = id_to_u16(source_str);
-
-        if source_str.ends_with('A') {
-            current_nodes.push(source);
-        }
-
-        let left = id_to_u16(&line[7..10]);
-        let right = id_to_u16(&line[12..15]);
-        graph[source as usize] = FastNode { left, right };
-    });
-
-    let mut loop_counts = vec![None; current_nodes.len()];
-
-    let mut steps = 0;
-    let mut direction_idx = 0;
-    loop {
-        let direction = &directions[direction_idx % directions.len()];
-        direction_idx += 1;
-
-        for i in 0..current_nodes.len() {
-            let current_node = &graph[current_nodes[i] as usize];
-
-            if current_nodes[i] & 0b0111110000000000 == 0b0110100000000000 // This checks if the final letter is a Z.
-                && loop_counts[i].is_none()
-            {
-                loop_counts[i] = Some(steps);
-            }
-
-            current_nodes[i] = match direction {
-                Direction::Left => current_node.left,
-                Direction::Right => current_node.right,
-            };
-        }
-
-        if loop_counts.iter().all(Option::is_some) {
-            break;
-        };
-
-        steps += 1;
+    match part_two(input, step_budget) {
+        Ok(steps) => println!("Part two: {}", steps),
+        Err(e) => println!("Part two: {}", e),
     }
-
-    loop_counts
-        .iter()
-        .fold(1, |acc, i| num::integer::lcm(acc, i.unwrap()))
-}
-
-fn main() {
-    let input = include_str!("../input.txt");
-
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
 }
 
 #[cfg(test)]
@@ -133,18 +62,25 @@ mod test {
     #[test]
     fn test_part_one_single() {
         let input = include_str!("../test_files/part_one_single.txt");
-        assert_eq!(part_one(input), 2)
+        assert_eq!(part_one(input, None), Ok(2))
     }
 
     #[test]
     fn test_part_one_looping() {
         let input = include_str!("../test_files/part_one_looping.txt");
-        assert_eq!(part_one(input), 6)
+        assert_eq!(part_one(input, None), Ok(6))
     }
 
     #[test]
     fn test_part_two() {
         let input = include_str!("../test_files/part_two.txt");
-        assert_eq!(part_two(input), 6)
+        assert_eq!(part_two(input, None), Ok(6))
+    }
+
+    #[test]
+    fn test_no_start() {
+        let input = "L\n\nBBB = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(part_one(input, None), Err(WalkError::NoStart));
+        assert_eq!(part_two(input, None), Err(WalkError::NoStart));
     }
 }
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn from_char(c: char) -> Self {
+        match c {
+            'L' => Direction::Left,
+            'R' => Direction::Right,
+            _ => panic!("Invalid direction in input"),
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WalkError {
+    NoStart,
+    Unreachable(Vec<String>),
+    StepBudgetExceeded(u64),
+    NoSimultaneousArrival,
+    Overflow,
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WalkError::NoStart => write!(f, "No node matches the start"),
+            WalkError::Unreachable(starts) => {
+                write!(f, "No target can be reached from {}", starts.join(", "))
+            }
+            WalkError::StepBudgetExceeded(budget) => {
+                write!(f, "No arrival within the budget of {} steps", budget)
+            }
+            WalkError::NoSimultaneousArrival => write!(f, "The walks never all arrive at once"),
+            WalkError::Overflow => write!(f, "The number of steps is too large to represent"),
+        }
+    }
+}
+
+// Node names are interned to dense ids, which index straight into `left` and `right`.
+pub struct Network {
+    pub directions: Vec<Direction>,
+    names: Vec<String>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+}
+
+fn intern<'a>(ids: &mut HashMap<&'a str, usize>, names: &mut Vec<&'a str>, name: &'a str) -> usize {
+    *ids.entry(name).or_insert_with(|| {
+        names.push(name);
+        names.len() - 1
+    })
+}
+
+impl Network {
+    pub fn from_str(input: &str) -> Self {
+        let mut input = input.lines();
+        let directions = input
+            .next()
+            .unwrap()
+            .trim()
+            .chars()
+            .map(Direction::from_char)
+            .collect();
+        input.next();
+
+        let mut ids = HashMap::new();
+        let mut names = Vec::new();
+        let mut edges = Vec::new();
+        input
+            .filter(|line| !line.trim().is_empty())
+            .for_each(|line| {
+                let (source, targets) = line.split_once('=').expect("Invalid node in input");
+                let (left, right) = targets
+                    .trim()
+                    .strip_prefix('(')
+                    .and_then(|t| t.strip_suffix(')'))
+                    .and_then(|t| t.split_once(','))
+                    .expect("Invalid node in input");
+                let source = intern(&mut ids, &mut names, source.trim());
+                let left = intern(&mut ids, &mut names, left.trim());
+                let right = intern(&mut ids, &mut names, right.trim());
+                edges.push((source, left, right));
+            });
+
+        let mut adjacency = vec![None; names.len()];
+        for (source, left, right) in edges {
+            adjacency[source] = Some((left, right));
+        }
+        let (left, right) = adjacency
+            .iter()
+            .enumerate()
+            .map(|(id, edges)| match edges {
+                Some(edges) => *edges,
+                None => panic!("Node {} is never defined", names[id]),
+            })
+            .unzip();
+
+        Network {
+            directions,
+            names: names.into_iter().map(str::to_string).collect(),
+            left,
+            right,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    pub fn next(&self, id: usize, direction: Direction) -> usize {
+        match direction {
+            Direction::Left => self.left[id],
+            Direction::Right => self.right[id],
+        }
+    }
+
+    pub fn nodes_where(&self, predicate: impl Fn(&str) -> bool) -> Vec<usize> {
+        (0..self.len())
+            .filter(|id| predicate(self.name(*id)))
+            .collect()
+    }
+
+    // Evaluating the predicate once per node keeps it out of the walk itself.
+    pub fn mask(&self, predicate: impl Fn(&str) -> bool) -> Vec<bool> {
+        self.names.iter().map(|name| predicate(name)).collect()
+    }
+
+    // Walks until a target, or until a (node, instruction index) state repeats after which
+    // nothing new can be visited. The budget caps the walk itself, so no start costs more
+    // than that many steps.
+    pub fn first_target(
+        &self,
+        start: usize,
+        is_target: &[bool],
+        step_budget: Option<u64>,
+    ) -> Result<Option<u64>, WalkError> {
+        let directions = self.directions.len();
+        let mut seen = vec![false; self.len() * directions];
+        let mut current = start;
+        let mut steps = 0;
+        loop {
+            if is_target[current] {
+                return Ok(Some(steps));
+            }
+            let state = current * directions + steps as usize % directions;
+            if seen[state] {
+                return Ok(None);
+            }
+            if step_budget == Some(steps) {
+                return Err(WalkError::StepBudgetExceeded(steps));
+            }
+            seen[state] = true;
+            current = self.next(current, self.directions[steps as usize % directions]);
+            steps += 1;
+        }
+    }
+
+    pub fn check_reachable(
+        &self,
+        starts: &[usize],
+        is_target: &[bool],
+        step_budget: Option<u64>,
+    ) -> Result<Vec<u64>, WalkError> {
+        let mut arrivals = vec![];
+        let mut unreachable = vec![];
+        for start in starts {
+            match self.first_target(*start, is_target, step_budget)? {
+                Some(steps) => arrivals.push(steps),
+                None => unreachable.push(self.name(*start).to_string()),
+            }
+        }
+        if unreachable.is_empty() {
+            Ok(arrivals)
+        } else {
+            Err(WalkError::Unreachable(unreachable))
+        }
+    }
+
+    pub fn first_arrivals(
+        &self,
+        is_start: impl Fn(&str) -> bool,
+        is_target: impl Fn(&str) -> bool,
+        step_budget: Option<u64>,
+    ) -> Result<Vec<u64>, WalkError> {
+        let is_target = self.mask(is_target);
+        let starts = self.nodes_where(is_start);
+        if starts.is_empty() {
+            return Err(WalkError::NoStart);
+        }
+        self.check_reachable(&starts, &is_target, step_budget)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_long_names() {
+        let network = Network::from_str(
+            "LR\n\nstart = (middle, start)\nmiddle = (x, end)\nend = (end, end)\nx = (x, x)",
+        );
+        assert_eq!(network.len(), 4);
+        assert_eq!(
+            network.first_arrivals(|n| n == "start", |n| n == "end", None),
+            Ok(vec![2])
+        );
+    }
+
+    #[test]
+    fn test_no_start() {
+        let network = Network::from_str("L\n\nBBB = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)");
+        assert_eq!(
+            network.first_arrivals(|n| n == "AAA", |n| n == "ZZZ", None),
+            Err(WalkError::NoStart)
+        );
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let network = Network::from_str(include_str!("../test_files/part_one_single.txt"));
+        // CCC does have an edge to ZZZ, but the instructions never take it.
+        assert_eq!(
+            network.first_arrivals(|n| n != "ZZZ", |n| n == "ZZZ", None),
+            Err(WalkError::Unreachable(vec![
+                "BBB".to_string(),
+                "CCC".to_string(),
+                "DDD".to_string(),
+                "EEE".to_string(),
+                "GGG".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_step_budget() {
+        let network = Network::from_str(include_str!("../test_files/part_one_looping.txt"));
+        let is_target = network.mask(|n| n == "ZZZ");
+        let start = network.nodes_where(|n| n == "AAA")[0];
+        assert_eq!(
+            network.first_target(start, &is_target, Some(6)),
+            Ok(Some(6))
+        );
+        assert_eq!(
+            network.first_target(start, &is_target, Some(5)),
+            Err(WalkError::StepBudgetExceeded(5))
+        );
+    }
+}
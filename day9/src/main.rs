@@ -1,80 +1,82 @@
-fn estimate_next(input: Vec<i32>) -> i32 {
-    let mut tree = vec![input];
+use num::{BigInt, ToPrimitive};
 
-    create_tree(&mut tree);
+mod polynomial;
+mod predictor;
+mod sequence;
 
-    tree.iter_mut().for_each(|v| v.push(0));
+use predictor::Predictor;
+use sequence::{SequenceError, Unconfirmed};
 
-    for idx in (0..tree.len() - 1).rev() {
-        let len = tree[idx].len();
-        let new_value = tree[idx + 1][len - 2] + tree[idx][len - 2];
-        tree[idx][len - 1] = new_value;
-    }
-
-    *tree.first().unwrap().iter().last().unwrap()
+fn parse_line(line: &str) -> Vec<BigInt> {
+    line.split_whitespace()
+        .map(|c| c.parse().unwrap())
+        .collect()
 }
 
-fn create_tree(tree: &mut Vec<Vec<i32>>) {
-    let mut idx = 0;
-    loop {
-        tree.push(Vec::new());
-        for i in 0..(tree[idx].len() - 1) {
-            let new_value = tree[idx][i + 1] - tree[idx][i];
-            tree[idx + 1].push(new_value)
-        }
+fn estimate_next(input: &[BigInt], policy: Unconfirmed) -> Result<Option<BigInt>, SequenceError> {
+    let polynomial = sequence::fit(input, policy)?;
+    Ok(polynomial.map(|p| p.at(input.len() as i64)))
+}
 
-        idx += 1;
+fn estimate_prev(input: &[BigInt], policy: Unconfirmed) -> Result<Option<BigInt>, SequenceError> {
+    let polynomial = sequence::fit(input, policy)?;
+    Ok(polynomial.map(|p| p.at(-1)))
+}
 
-        if tree[idx].iter().all(|v| *v == 0) {
-            break;
+fn sum_estimates(
+    input: &str,
+    estimate: impl Fn(&[BigInt]) -> Result<Option<BigInt>, SequenceError>,
+) -> Result<i64, SequenceError> {
+    let mut total = BigInt::from(0);
+    for line in input.lines() {
+        if let Some(value) = estimate(&parse_line(line))? {
+            total += value;
         }
     }
+    Ok(total.to_i64().expect("The sum does not fit in an i64"))
 }
 
-fn part_one(input: &str) -> i32 {
-    input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|c| c.parse().unwrap())
-                .collect()
-        })
-        .map(estimate_next)
-        .sum()
+fn part_one(input: &str, policy: Unconfirmed) -> Result<i64, SequenceError> {
+    sum_estimates(input, |values| estimate_next(values, policy))
 }
 
-fn estimate_prev(input: Vec<i32>) -> i32 {
-    let mut tree = vec![input];
-
-    create_tree(&mut tree);
-
-    tree.iter_mut().for_each(|v| v.insert(0, 0));
-
-    for idx in (0..tree.len() - 1).rev() {
-        let new_value = tree[idx][1] - tree[idx + 1][0];
-        tree[idx][0] = new_value;
-    }
-
-    *tree.first().unwrap().first().unwrap()
+fn part_two(input: &str, policy: Unconfirmed) -> Result<i64, SequenceError> {
+    sum_estimates(input, |values| estimate_prev(values, policy))
 }
 
-fn part_two(input: &str) -> i32 {
-    input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|c| c.parse().unwrap())
-                .collect()
-        })
-        .map(estimate_prev)
-        .sum()
+// Reads values from stdin as they arrive, printing the running predictions after each one.
+fn stream() {
+    let mut predictor = Predictor::new();
+    for line in std::io::stdin().lines() {
+        for value in line.unwrap().split_whitespace() {
+            predictor.push(value.parse::<BigInt>().unwrap());
+            println!(
+                "{} -> next {}, prev {}",
+                value,
+                predictor.predict_next().unwrap(),
+                predictor.predict_prev().unwrap()
+            );
+        }
+    }
 }
 
 fn main() {
     let input = include_str!("../input.txt");
-
-    println!("Part one: {}", part_one(input));
-    println!("Part two: {}", part_two(input));
+    // Sequences that aren't polynomial can be skipped, fail the run, or use their best fit.
+    let policy = match std::env::args().nth(1) {
+        Some(arg) if arg == "stream" => return stream(),
+        Some(arg) => Unconfirmed::from_str(&arg).expect("Expected skip, error or best-fit"),
+        None => Unconfirmed::Error,
+    };
+
+    match part_one(input, policy) {
+        Ok(total) => println!("Part one: {}", total),
+        Err(e) => println!("Part one: {}", e),
+    }
+    match part_two(input, policy) {
+        Ok(total) => println!("Part two: {}", total),
+        Err(e) => println!("Part two: {}", e),
+    }
 }
 
 #[cfg(test)]
@@ -84,12 +86,12 @@ mod test {
     #[test]
     fn test_part_one() {
         let input = include_str!("../test_files/part_one_and_two.txt");
-        assert_eq!(part_one(input), 114)
+        assert_eq!(part_one(input, Unconfirmed::Error), Ok(114))
     }
 
     #[test]
     fn test_part_two() {
         let input = include_str!("../test_files/part_one_and_two.txt");
-        assert_eq!(part_two(input), 2)
+        assert_eq!(part_two(input, Unconfirmed::Error), Ok(2))
     }
 }
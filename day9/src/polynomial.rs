@@ -0,0 +1,79 @@
+use num::{BigInt, One, Zero};
+
+// A polynomial in Newton form: `differences[k]` is the k-th forward difference at index 0,
+// so the value at x is the sum of `differences[k] * C(x, k)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial {
+    differences: Vec<BigInt>,
+}
+
+impl Polynomial {
+    // The unique polynomial of degree below `values.len()` through every value, with the
+    // first value at index 0.
+    pub fn interpolate(values: &[BigInt]) -> Self {
+        if values.is_empty() {
+            panic!("Can not interpolate an empty sequence");
+        }
+        let mut row = values.to_vec();
+        let mut differences = Vec::with_capacity(values.len());
+        while let Some(first) = row.first() {
+            differences.push(first.clone());
+            row = row.windows(2).map(|w| &w[1] - &w[0]).collect();
+        }
+        while differences.len() > 1 && differences.last().unwrap().is_zero() {
+            differences.pop();
+        }
+        Polynomial { differences }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.differences.len() - 1
+    }
+
+    pub fn evaluate(&self, x: &BigInt) -> BigInt {
+        // C(x, k) is built up one factor at a time; each division is exact, also for negative x.
+        let mut binomial = BigInt::one();
+        let mut total = BigInt::zero();
+        for (k, difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                binomial = binomial * (x - (k - 1)) / k;
+            }
+            total += difference * &binomial;
+        }
+        total
+    }
+
+    pub fn at(&self, x: i64) -> BigInt {
+        self.evaluate(&BigInt::from(x))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(input: &[i64]) -> Vec<BigInt> {
+        input.iter().map(|v| BigInt::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_reproduces_values() {
+        let input = [10, 13, 16, 21, 30, 45];
+        let polynomial = Polynomial::interpolate(&values(&input));
+        for (i, v) in input.iter().enumerate() {
+            assert_eq!(polynomial.at(i as i64), BigInt::from(*v));
+        }
+        assert_eq!(polynomial.at(6), BigInt::from(68));
+        assert_eq!(polynomial.at(-1), BigInt::from(5));
+    }
+
+    #[test]
+    fn test_far_positions() {
+        // x^3 - 2x + 7, far outside the range it was sampled in.
+        let f = |x: i64| BigInt::from(x).pow(3) - 2 * BigInt::from(x) + 7;
+        let polynomial = Polynomial::interpolate(&(0..4).map(f).collect::<Vec<_>>());
+        for x in [-1_000_000_000_000, -17, 100, 3_000_000_000_000_000] {
+            assert_eq!(polynomial.at(x), f(x));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use num::{BigInt, Zero};
+
+// Keeps only the diagonals of the difference table, the last value of each row for
+// predicting forwards and the first for predicting backwards. Rows past the end of either
+// diagonal are all zero, so for polynomial data both stay at the degree plus one.
+#[derive(Default)]
+pub struct Predictor {
+    samples: usize,
+    last: Vec<BigInt>,
+    first: Vec<BigInt>,
+}
+
+impl Predictor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, value: impl Into<BigInt>) {
+        let mut carry = value.into();
+        for last in self.last.iter_mut() {
+            let next = &carry - &*last;
+            *last = carry;
+            carry = next;
+        }
+
+        // A row that was implicitly zero gets the same new value in every row below it, down
+        // to the row the new sample creates.
+        if !carry.is_zero() {
+            let rows = self.samples + 1;
+            self.last.resize(rows, carry.clone());
+            self.first.resize(self.samples, BigInt::zero());
+            self.first.push(carry);
+        }
+        while self.last.last().is_some_and(Zero::is_zero) {
+            self.last.pop();
+        }
+        self.samples += 1;
+    }
+
+    pub fn predict_next(&self) -> Option<BigInt> {
+        if self.samples == 0 {
+            return None;
+        }
+        Some(self.last.iter().sum())
+    }
+
+    pub fn predict_prev(&self) -> Option<BigInt> {
+        if self.samples == 0 {
+            return None;
+        }
+        Some(
+            self.first
+                .iter()
+                .enumerate()
+                .map(|(k, first)| if k % 2 == 0 { first.clone() } else { -first })
+                .sum(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sequence::Unconfirmed;
+    use crate::{estimate_next, estimate_prev, parse_line};
+
+    #[test]
+    fn test_matches_estimates() {
+        for line in include_str!("../test_files/part_one_and_two.txt").lines() {
+            let values = parse_line(line);
+            let mut predictor = Predictor::new();
+            for (i, value) in values.iter().enumerate() {
+                predictor.push(value.clone());
+                // Short prefixes aren't confirmed polynomials yet, so compare with their best fit.
+                let prefix = &values[..=i];
+                assert_eq!(
+                    predictor.predict_next(),
+                    estimate_next(prefix, Unconfirmed::BestFit).unwrap()
+                );
+                assert_eq!(
+                    predictor.predict_prev(),
+                    estimate_prev(prefix, Unconfirmed::BestFit).unwrap()
+                );
+            }
+            assert_eq!(
+                predictor.predict_next(),
+                estimate_next(&values, Unconfirmed::Error).unwrap()
+            );
+            assert_eq!(
+                predictor.predict_prev(),
+                estimate_prev(&values, Unconfirmed::Error).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_diagonal_stays_small() {
+        let mut predictor = Predictor::new();
+        for x in 0..1000i64 {
+            predictor.push(x * x - 3 * x);
+        }
+        assert_eq!(predictor.last.len(), 3);
+        assert_eq!(predictor.first.len(), 3);
+        assert_eq!(
+            predictor.predict_next(),
+            Some(BigInt::from(1000 * 1000 - 3000))
+        );
+        assert_eq!(predictor.predict_prev(), Some(BigInt::from(4)));
+    }
+
+    #[test]
+    fn test_noise_after_pattern() {
+        let mut predictor = Predictor::new();
+        for value in [0, 0, 0, 5] {
+            predictor.push(value);
+        }
+        // Every row of the table now ends in 5.
+        assert_eq!(predictor.predict_next(), Some(BigInt::from(20)));
+        assert_eq!(predictor.predict_prev(), Some(BigInt::from(-5)));
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Predictor::new().predict_next(), None);
+        assert_eq!(Predictor::new().predict_prev(), None);
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+use num::{BigInt, Zero};
+
+use crate::polynomial::Polynomial;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SequenceError {
+    Empty,
+    // The differences never became all zero, so the values only pin down the polynomial of
+    // the highest degree their length allows.
+    NotPolynomial { best_fit: Polynomial },
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SequenceError::Empty => write!(f, "Sequence has no values"),
+            SequenceError::NotPolynomial { best_fit } => write!(
+                f,
+                "Sequence is not polynomial within its length, best fit has degree {}",
+                best_fit.degree()
+            ),
+        }
+    }
+}
+
+// What to do with a sequence whose degree its values can not confirm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unconfirmed {
+    Skip,
+    Error,
+    BestFit,
+}
+
+impl Unconfirmed {
+    pub fn from_str(input: &str) -> Option<Self> {
+        match input {
+            "skip" => Some(Self::Skip),
+            "error" => Some(Self::Error),
+            "best-fit" => Some(Self::BestFit),
+            _ => None,
+        }
+    }
+}
+
+pub fn analyse(values: &[BigInt]) -> Result<Polynomial, SequenceError> {
+    if values.is_empty() {
+        return Err(SequenceError::Empty);
+    }
+    let mut row = values.to_vec();
+    while !row.is_empty() {
+        if row.iter().all(Zero::is_zero) {
+            return Ok(Polynomial::interpolate(values));
+        }
+        row = row.windows(2).map(|w| &w[1] - &w[0]).collect();
+    }
+    Err(SequenceError::NotPolynomial {
+        best_fit: Polynomial::interpolate(values),
+    })
+}
+
+pub fn fit(values: &[BigInt], policy: Unconfirmed) -> Result<Option<Polynomial>, SequenceError> {
+    match (analyse(values), policy) {
+        (Ok(polynomial), _) => Ok(Some(polynomial)),
+        (Err(_), Unconfirmed::Skip) => Ok(None),
+        (Err(SequenceError::NotPolynomial { best_fit }), Unconfirmed::BestFit) => {
+            Ok(Some(best_fit))
+        }
+        (Err(e), _) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(input: &[i64]) -> Vec<BigInt> {
+        input.iter().map(|v| BigInt::from(*v)).collect()
+    }
+
+    #[test]
+    fn test_degree() {
+        assert_eq!(analyse(&values(&[0, 3, 6, 9, 12, 15])).unwrap().degree(), 1);
+        assert_eq!(
+            analyse(&values(&[10, 13, 16, 21, 30, 45]))
+                .unwrap()
+                .degree(),
+            3
+        );
+        assert_eq!(analyse(&values(&[4, 4])).unwrap().degree(), 0);
+        assert_eq!(analyse(&values(&[0])).unwrap().degree(), 0);
+    }
+
+    #[test]
+    fn test_unconfirmed() {
+        assert_eq!(analyse(&[]), Err(SequenceError::Empty));
+        assert!(matches!(
+            analyse(&values(&[7])),
+            Err(SequenceError::NotPolynomial { .. })
+        ));
+
+        let noise = values(&[3, -1, 4, 1, -5]);
+        assert_eq!(fit(&noise, Unconfirmed::Skip), Ok(None));
+        assert!(fit(&noise, Unconfirmed::Error).is_err());
+        let best_fit = fit(&noise, Unconfirmed::BestFit).unwrap().unwrap();
+        assert_eq!(best_fit.degree(), 4);
+        assert_eq!(best_fit.at(4), BigInt::from(-5));
+    }
+}